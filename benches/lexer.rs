@@ -0,0 +1,50 @@
+//! Compares the throughput of `lexer_from_str` against the zero-copy lexer
+//! on a large generated source file.
+use baros::parser::borrowed::borrowed_lexer_from_str;
+use baros::parser::lexer::lexer_from_str;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::hint::black_box;
+
+const SAMPLE: &str = r#"//! A module of generated code
+/// Adds up the values in a range
+pub fun sum_range(start, end) -> Int {
+    var total = 0
+    for value in start..end {
+        total += value * 2 - 1
+    }
+    /* the total can be large,
+       so it is returned as is */
+    return total
+}
+
+let _unused = 0x1F_FF + 0b1010 - 0o17
+let message = "hello \"world\"\n\u{1F600}"
+let ratio = 2.5e-3 ** 2 %% 7
+if total >= 1_000 && message != "" { print(message) } elif ratio <> 0.5 { tuple.0.1 }
+"#;
+
+/// Roughly four megabytes of source
+fn large_source() -> String {
+    SAMPLE.repeat(4 * 1024 * 1024 / SAMPLE.len())
+}
+
+fn lexers(c: &mut Criterion) {
+    let src = large_source();
+    assert!(lexer_from_str(&src).all(|tok| tok.is_ok()));
+
+    let mut group = c.benchmark_group("lex");
+    group.throughput(Throughput::Bytes(src.len() as u64));
+    group.sample_size(10);
+
+    group.bench_function("lexer_from_str", |b| {
+        b.iter(|| lexer_from_str(black_box(&src)).count())
+    });
+    group.bench_function("borrowed_lexer_from_str", |b| {
+        b.iter(|| borrowed_lexer_from_str(black_box(&src)).count())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, lexers);
+criterion_main!(benches);
@@ -0,0 +1,34 @@
+//! Checks shared by the lexer fuzz targets
+use baros::parser::lexer::{lexer_from_str, source_span};
+use baros::util::Span;
+
+/// Lexes `src` up to the end of the file or the first error, checking that the lexer
+/// keeps making progress and that every span is in bounds, on character boundaries and after the last one
+pub fn check(src: &str) {
+    let limit = src.chars().count() + 1;
+    let mut last = 0;
+    let mut count = 0;
+
+    for result in lexer_from_str(src) {
+        count += 1;
+        assert!(count <= limit, "lexer made no progress on {src:?}");
+
+        match result {
+            Ok(tok) => {
+                let span = source_span(&tok);
+                check_span(src, span);
+                assert!(span.start >= last);
+                last = span.end;
+            }
+            Err(err) => {
+                check_span(src, err.location);
+                break;
+            }
+        }
+    }
+}
+
+fn check_span(src: &str, span: Span) {
+    assert!(span.start <= span.end && span.end as usize <= src.len());
+    assert!(src.is_char_boundary(span.start as usize) && src.is_char_boundary(span.end as usize));
+}
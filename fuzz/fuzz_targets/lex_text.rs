@@ -0,0 +1,9 @@
+//! Lexes arbitrary UTF-8
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+mod common;
+
+fuzz_target!(|src: &str| {
+    common::check(src);
+});
@@ -0,0 +1,45 @@
+//! Lexes source built out of keywords, symbols and literal fragments
+//! Each input byte picks the next fragment, so the fuzzer mostly explores combinations of tokens.
+#![no_main]
+use baros::parser::lexer::KEYWORDS;
+use baros::parser::token::SYMBOLS;
+use libfuzzer_sys::fuzz_target;
+
+mod common;
+
+const FRAGMENTS: [&str; 16] = [
+    " ",
+    "\n",
+    "\r\n",
+    "x",
+    "_y",
+    "@z",
+    "0",
+    "-1",
+    "1.5e3",
+    "0x1F",
+    "\"s\\n\"",
+    "\"\\u{1F600}\"",
+    "// c\n",
+    "/// d\n",
+    "/* m */",
+    "\"",
+];
+
+fuzz_target!(|data: &[u8]| {
+    let mut src = String::new();
+
+    for byte in data {
+        let index = *byte as usize;
+
+        if let Some(fragment) = FRAGMENTS.get(index) {
+            src.push_str(fragment);
+        } else if let Some((keyword, _)) = KEYWORDS.get(index - FRAGMENTS.len()) {
+            src.push_str(keyword);
+        } else if let Some(symbol) = SYMBOLS.get(index - FRAGMENTS.len() - KEYWORDS.len()) {
+            src.push_str(&symbol.to_string());
+        }
+    }
+
+    common::check(&src);
+});
@@ -0,0 +1,33 @@
+//! Syntax highlighting of Baros source, driven by `TokenKind`
+use crate::parser::lexer::{lexer_from_str, source_span};
+use crate::parser::token::TokenKind;
+
+pub mod ansi;
+pub mod html;
+pub mod textmate;
+pub mod tree_sitter;
+
+/// Splits `src` into runs of text paired with the kind of token they belong to
+/// Whitespace between tokens has no kind, and neither does anything after a lexing error.
+pub fn segments(src: &str) -> Vec<(&str, Option<TokenKind>)> {
+    let mut segments = Vec::new();
+    let mut pos = 0;
+
+    for tok in lexer_from_str(src).map_while(Result::ok) {
+        let span = source_span(&tok);
+        let (start, end) = (span.start as usize, (span.end as usize).min(src.len()));
+
+        if pos < start {
+            segments.push((&src[pos..start], None));
+        }
+
+        segments.push((&src[start..end], Some(tok.0.kind())));
+        pos = end;
+    }
+
+    if pos < src.len() {
+        segments.push((&src[pos..], None));
+    }
+
+    segments
+}
@@ -0,0 +1,38 @@
+//! Highlights source for display in a terminal
+use super::segments;
+use crate::parser::token::TokenKind;
+
+const RESET: &str = "\x1b[0m";
+
+/// The ANSI escape sequence used to colour a `TokenKind`
+pub fn style(kind: TokenKind) -> Option<&'static str> {
+    match kind {
+        TokenKind::Keyword => Some("\x1b[35m"),
+        TokenKind::Operator => Some("\x1b[36m"),
+        TokenKind::ReservedOperator => Some("\x1b[31m"),
+        TokenKind::Literal => Some("\x1b[32m"),
+        TokenKind::Comment => Some("\x1b[90m"),
+        TokenKind::DocComment => Some("\x1b[33m"),
+        TokenKind::UnusedIdentifier => Some("\x1b[2m"),
+        TokenKind::Delimiter => Some("\x1b[1m"),
+        TokenKind::Identifier | TokenKind::Punctuation | TokenKind::Control => None,
+    }
+}
+
+/// Highlights `src` with ANSI escape sequences
+pub fn highlight(src: &str) -> String {
+    let mut out = String::with_capacity(src.len());
+
+    for (text, kind) in segments(src) {
+        match kind.and_then(style) {
+            Some(style) => {
+                out.push_str(style);
+                out.push_str(text);
+                out.push_str(RESET);
+            }
+            None => out.push_str(text),
+        }
+    }
+
+    out
+}
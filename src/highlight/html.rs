@@ -0,0 +1,39 @@
+//! Highlights source as HTML
+//! Every token other than newlines is wrapped in a `<span>` with the class `bs-` followed by its kind.
+use super::segments;
+use crate::parser::token::TokenKind;
+
+/// Highlights `src` as a `<pre class="baros">` element
+pub fn highlight(src: &str) -> String {
+    let mut out = String::from("<pre class=\"baros\">");
+
+    for (text, kind) in segments(src) {
+        match kind {
+            Some(kind) if kind != TokenKind::Control => {
+                out.push_str("<span class=\"bs-");
+                out.push_str(kind.as_str());
+                out.push_str("\">");
+                escape(&mut out, text);
+                out.push_str("</span>");
+            }
+            _ => escape(&mut out, text),
+        }
+    }
+
+    out.push_str("</pre>");
+    out
+}
+
+/// Pushes `text` onto `out`, escaping characters that are special in HTML
+fn escape(out: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            ch => out.push(ch),
+        }
+    }
+}
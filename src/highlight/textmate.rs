@@ -0,0 +1,141 @@
+//! Generates a TextMate grammar for Baros
+//! The keyword and operator patterns are built from `KEYWORDS` and `SYMBOLS`,
+//! so the grammar always matches what the lexer accepts.
+use crate::parser::lexer::KEYWORDS;
+use crate::parser::token::{TokenKind, SYMBOLS};
+
+/// Symbols the lexer never produces: `;` and `=>` are rejected, and `@` and `_` start names
+const UNLEXED: [&str; 4] = [";", "=>", "@", "_"];
+
+/// The TextMate scope used for a `TokenKind`
+pub fn scope(kind: TokenKind) -> &'static str {
+    match kind {
+        TokenKind::Keyword => "keyword.control.baros",
+        TokenKind::Operator => "keyword.operator.baros",
+        TokenKind::ReservedOperator => "keyword.operator.reserved.baros",
+        TokenKind::Literal => "constant.other.baros",
+        TokenKind::Comment => "comment.line.double-slash.baros",
+        TokenKind::DocComment => "comment.line.documentation.baros",
+        TokenKind::Identifier => "variable.other.baros",
+        TokenKind::UnusedIdentifier => "variable.other.unused.baros",
+        TokenKind::Punctuation => "punctuation.separator.baros",
+        TokenKind::Delimiter => "punctuation.section.baros",
+        TokenKind::Control => "meta.control.baros",
+    }
+}
+
+/// Regex matching any of the keywords
+pub fn keyword_pattern() -> String {
+    let words: Vec<&str> = KEYWORDS.iter().map(|(spelling, _)| *spelling).collect();
+    format!("\\b({})\\b", words.join("|"))
+}
+
+/// The spellings of every symbol of the given `kind` that the lexer produces, longest first
+pub fn symbols(kind: TokenKind) -> Vec<String> {
+    let mut symbols: Vec<String> = SYMBOLS
+        .iter()
+        .filter(|tok| tok.kind() == kind)
+        .map(|tok| tok.to_string())
+        .filter(|symbol| !UNLEXED.contains(&symbol.as_str()))
+        .collect();
+    symbols.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+    symbols
+}
+
+/// Generates the grammar as TextMate JSON
+pub fn grammar() -> String {
+    let mut patterns = vec![
+        single_pattern(scope(TokenKind::DocComment), "//[/!].*$"),
+        single_pattern(scope(TokenKind::Comment), "//.*$"),
+        format!(
+            "{{ \"name\": \"comment.block.baros\", \"begin\": {}, \"end\": {} }}",
+            json_string("/\\*"),
+            json_string("\\*/")
+        ),
+        format!(
+            "{{ \"name\": \"string.quoted.double.baros\", \"begin\": \"\\\"\", \"end\": \"\\\"\", \"patterns\": [{}] }}",
+            single_pattern(
+                "constant.character.escape.baros",
+                "\\\\([fnrt\"\\\\]|u\\{[0-9a-fA-F]{1,6}\\})"
+            )
+        ),
+        single_pattern(
+            "constant.numeric.baros",
+            "-?\\b(0[xX][0-9a-fA-F_]+|0[oO][0-7_]+|0[bB][01_]+|[0-9][0-9_]*(\\.[0-9_]*([eE][+-]?[0-9_]*)?)?)",
+        ),
+        single_pattern(scope(TokenKind::Keyword), &keyword_pattern()),
+        single_pattern(scope(TokenKind::UnusedIdentifier), "\\b_[_0-9A-Za-z]*"),
+        single_pattern(scope(TokenKind::Identifier), "@?\\b[A-Za-z][_0-9A-Za-z]*"),
+    ];
+
+    // rules are tried in order, so symbols are grouped by length to keep `->` from matching as `-`
+    let longest = SYMBOLS
+        .iter()
+        .map(|tok| tok.to_string().len())
+        .max()
+        .unwrap_or(0);
+
+    for len in (1..=longest).rev() {
+        for kind in [
+            TokenKind::ReservedOperator,
+            TokenKind::Operator,
+            TokenKind::Punctuation,
+            TokenKind::Delimiter,
+        ] {
+            let escaped: Vec<String> = symbols(kind)
+                .iter()
+                .filter(|symbol| symbol.len() == len)
+                .map(|symbol| escape_regex(symbol))
+                .collect();
+
+            if !escaped.is_empty() {
+                patterns.push(single_pattern(scope(kind), &escaped.join("|")));
+            }
+        }
+    }
+
+    format!(
+        "{{\n  \"name\": \"Baros\",\n  \"scopeName\": \"source.baros\",\n  \"fileTypes\": [\"bs\"],\n  \"patterns\": [\n    {}\n  ]\n}}\n",
+        patterns.join(",\n    ")
+    )
+}
+
+/// A grammar rule matching `regex` as a whole
+fn single_pattern(name: &str, regex: &str) -> String {
+    format!(
+        "{{ \"name\": {}, \"match\": {} }}",
+        json_string(name),
+        json_string(regex)
+    )
+}
+
+/// Escapes characters that are special in regular expressions
+fn escape_regex(s: &str) -> String {
+    let mut out = String::new();
+
+    for ch in s.chars() {
+        if "\\^$.|?*+()[]{}/".contains(ch) {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+
+    out
+}
+
+/// Quotes `s` as a JSON string
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            ch => out.push(ch),
+        }
+    }
+
+    out.push('"');
+    out
+}
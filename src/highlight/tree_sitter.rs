@@ -0,0 +1,111 @@
+//! Generates a tree-sitter grammar for Baros
+//! Like the TextMate grammar, keywords and symbols come from `KEYWORDS` and `SYMBOLS`.
+//! The grammar only describes tokens, as there is no parser for it to mirror yet.
+use super::textmate::symbols;
+use crate::parser::lexer::KEYWORDS;
+use crate::parser::token::TokenKind;
+
+/// Name of the tree-sitter rule holding tokens of a `TokenKind`
+pub fn rule_name(kind: TokenKind) -> &'static str {
+    match kind {
+        TokenKind::Keyword => "keyword",
+        TokenKind::Operator => "operator",
+        TokenKind::ReservedOperator => "reserved_operator",
+        TokenKind::Literal => "literal",
+        TokenKind::Comment => "comment",
+        TokenKind::DocComment => "doc_comment",
+        TokenKind::Identifier => "identifier",
+        TokenKind::UnusedIdentifier => "unused_identifier",
+        TokenKind::Punctuation => "punctuation",
+        TokenKind::Delimiter => "delimiter",
+        TokenKind::Control => "newline",
+    }
+}
+
+/// Generates `grammar.js`
+pub fn grammar() -> String {
+    let keywords: Vec<String> = KEYWORDS
+        .iter()
+        .map(|(spelling, _)| js_string(spelling))
+        .collect();
+
+    let mut rules = vec![
+        "source_file: $ => repeat($._token)".to_string(),
+        format!(
+            "_token: $ => choice({})",
+            TokenKind::ALL
+                .iter()
+                .map(|kind| format!("$.{}", rule_name(*kind)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        format!("keyword: $ => choice({})", keywords.join(", ")),
+        "identifier: $ => /[@A-Za-z][_0-9A-Za-z]*/".to_string(),
+        "unused_identifier: $ => /_[_0-9A-Za-z]*/".to_string(),
+        "literal: $ => choice($.string, $.float, $.int)".to_string(),
+        "string: $ => token(seq('\"', repeat(choice(/[^\"\\\\]/, /\\\\([fnrt\"\\\\]|u\\{[0-9a-fA-F]{1,6}\\})/)), '\"'))"
+            .to_string(),
+        "float: $ => /-?[0-9][0-9_]*\\.[0-9_]*([eE][+-]?[0-9_]*)?/".to_string(),
+        "int: $ => /-?(0[xX][0-9a-fA-F_]+|0[oO][0-7_]+|0[bB][01_]+|[0-9][0-9_]*)/".to_string(),
+        "comment: $ => choice(token(seq('//', /[^/!\\r\\n][^\\r\\n]*/)), token('//'), token(seq('/*', /([^*]|\\*+[^*/])*/, /\\*+\\//)))"
+            .to_string(),
+        "doc_comment: $ => token(seq('//', /[/!][^\\r\\n]*/))".to_string(),
+        "newline: $ => /\\r?\\n|\\r/".to_string(),
+    ];
+
+    for kind in [
+        TokenKind::Operator,
+        TokenKind::ReservedOperator,
+        TokenKind::Punctuation,
+        TokenKind::Delimiter,
+    ] {
+        let spellings: Vec<String> = symbols(kind)
+            .iter()
+            .map(|symbol| js_string(symbol))
+            .collect();
+        rules.push(format!(
+            "{}: $ => choice({})",
+            rule_name(kind),
+            spellings.join(", ")
+        ));
+    }
+
+    format!(
+        "// Generated by baros::highlight::tree_sitter::grammar, do not edit by hand\n\
+         module.exports = grammar({{\n  name: 'baros',\n\n  extras: $ => [/[ \\t\\f]/],\n\n  word: $ => $.identifier,\n\n  rules: {{\n    {},\n  }},\n}});\n",
+        rules.join(",\n\n    ")
+    )
+}
+
+/// Generates `queries/highlights.scm`
+pub fn highlights() -> String {
+    let captures = [
+        ("keyword", "@keyword"),
+        ("operator", "@operator"),
+        ("reserved_operator", "@operator.reserved"),
+        ("string", "@string"),
+        ("int", "@number"),
+        ("float", "@number.float"),
+        ("comment", "@comment"),
+        ("doc_comment", "@comment.documentation"),
+        ("identifier", "@variable"),
+        ("unused_identifier", "@variable.unused"),
+        ("punctuation", "@punctuation.delimiter"),
+        ("delimiter", "@punctuation.bracket"),
+    ];
+
+    let mut out = String::from(
+        "; Generated by baros::highlight::tree_sitter::highlights, do not edit by hand\n",
+    );
+
+    for (node, capture) in captures {
+        out.push_str(&format!("({node}) {capture}\n"));
+    }
+
+    out
+}
+
+/// Quotes `s` as a JavaScript string
+fn js_string(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+}
@@ -1,2 +1,5 @@
+pub mod highlight;
+pub mod package;
 pub mod parser;
+pub mod source;
 pub mod util;
\ No newline at end of file
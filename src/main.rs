@@ -1,4 +1,74 @@
-use baros::lex::lexer;
-use baros::lex::token;
+use baros::package::commands::{self, VENDOR_DIR};
+use baros::package::manifest::discover;
+use baros::package::registry::Registry;
+use std::error::Error;
+use std::process::ExitCode;
 
-fn main() {}
+const USAGE: &str = "usage:
+    baros add <name> [version]   depend on a registry package
+    baros update [names...]      move registry packages to the newest versions that work
+    baros vendor                 copy locked registry packages into vendor/
+    baros publish                publish the current package to the registry
+
+The registry is the directory in BAROS_REGISTRY.
+Set it to a package's vendor directory to use its vendored packages instead,
+except when running baros vendor, which replaces that directory.";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[&str]) -> Result<(), Box<dyn Error>> {
+    let cwd = std::env::current_dir()?;
+    let registry = || {
+        std::env::var_os("BAROS_REGISTRY")
+            .map(Registry::open)
+            .ok_or("BAROS_REGISTRY is not set")
+    };
+
+    match args {
+        ["add", name, version @ ..] if version.len() <= 1 => {
+            let version = match version.first() {
+                Some(version) => Some(version.parse()?),
+                None => None,
+            };
+            let manifest = discover(&cwd)?;
+            let added = commands::add(&manifest, &registry()?, name, version)?;
+
+            println!("added {name} = \"{added}\"");
+        }
+        ["update", names @ ..] => {
+            let manifest = discover(&cwd)?;
+            let lockfile = commands::update(&manifest, &registry()?, names)?;
+
+            for locked in &lockfile.packages {
+                println!("{} {}", locked.name, locked.version);
+            }
+        }
+        ["vendor"] => {
+            let manifest = discover(&cwd)?;
+            let _ = commands::vendor(&manifest, &registry()?)?;
+
+            println!("vendored into {VENDOR_DIR}/");
+        }
+        ["publish"] => {
+            let manifest = discover(&cwd)?;
+            let root = manifest.parent().expect("manifests are in a directory");
+            let entry = registry()?.publish(root)?;
+
+            println!("published {}", entry.version);
+        }
+        _ => return Err(USAGE.into()),
+    }
+
+    Ok(())
+}
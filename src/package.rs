@@ -0,0 +1,7 @@
+pub mod commands;
+pub mod error;
+pub mod graph;
+pub mod lockfile;
+pub mod manifest;
+pub mod registry;
+pub mod resolver;
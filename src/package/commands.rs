@@ -0,0 +1,174 @@
+//! What `baros add`, `baros update` and `baros vendor` do to a package
+use super::error::{PackageError, PackageErrorType};
+use super::graph::PackageGraph;
+use super::lockfile::{Lockfile, LOCKFILE_NAME};
+use super::manifest::is_valid_name;
+use super::registry::{io_error, remove_dir, Registry};
+use super::resolver::resolve;
+use semver::VersionReq;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The directory `vendor` copies registry packages into, next to the manifest
+pub const VENDOR_DIR: &str = "vendor";
+
+/// Resolves the registry dependencies of the package at `manifest` and writes its lockfile
+/// Versions that are already locked are kept wherever they still meet the requirements.
+pub fn lock(manifest: &Path, registry: &Registry) -> Result<Lockfile, PackageError> {
+    let lockfile = Lockfile::load(&lockfile_path(manifest))?;
+    relock(manifest, registry, &lockfile)
+}
+
+/// Resolves the registry dependencies of the package at `manifest` again, moving the packages in `names`
+/// to the newest versions that work, or every package if `names` is empty
+pub fn update(
+    manifest: &Path,
+    registry: &Registry,
+    names: &[&str],
+) -> Result<Lockfile, PackageError> {
+    let prefer = match names {
+        [] => Lockfile::default(),
+        names => Lockfile::load(&lockfile_path(manifest))?.unlock(names),
+    };
+
+    relock(manifest, registry, &prefer)
+}
+
+/// Adds a dependency on the registry package `name` to the manifest at `manifest`, then locks it
+///
+/// Without a `version`, the requirement is compatibility with the newest published release.
+/// The manifest is left as it was if the dependencies can't be resolved with the new one.
+pub fn add(
+    manifest: &Path,
+    registry: &Registry,
+    name: &str,
+    version: Option<VersionReq>,
+) -> Result<VersionReq, PackageError> {
+    if !is_valid_name(name) {
+        return Err(PackageError::new(
+            PackageErrorType::InvalidName { name: name.into() },
+            manifest,
+        ));
+    }
+
+    let versions = registry.versions(name)?;
+    let version = match version {
+        Some(version) => version,
+        None => {
+            // prereleases are only picked when nothing else has been published
+            let newest = versions
+                .iter()
+                .find(|entry| entry.version.pre.is_empty())
+                .or(versions.first())
+                .map(|entry| &entry.version)
+                .ok_or_else(|| {
+                    PackageError::new(
+                        PackageErrorType::UnknownPackage { name: name.into() },
+                        registry.root(),
+                    )
+                })?;
+
+            VersionReq::parse(&format!("^{newest}")).expect("caret requirement")
+        }
+    };
+
+    let original = fs::read_to_string(manifest).map_err(|err| io_error(err, manifest))?;
+    let mut document: toml_edit::DocumentMut =
+        original.parse().map_err(|err: toml_edit::TomlError| {
+            PackageError::new(
+                PackageErrorType::Syntax {
+                    message: err.message().to_string(),
+                },
+                manifest,
+            )
+        })?;
+
+    let dependencies = document
+        .entry("dependencies")
+        .or_insert_with(toml_edit::table)
+        .as_table_like_mut()
+        .ok_or_else(|| {
+            PackageError::new(
+                PackageErrorType::Syntax {
+                    message: "`dependencies` is not a table".into(),
+                },
+                manifest,
+            )
+        })?;
+    let _ = dependencies.insert(name, toml_edit::value(version.to_string()));
+
+    fs::write(manifest, document.to_string()).map_err(|err| io_error(err, manifest))?;
+
+    match lock(manifest, registry) {
+        Ok(_) => Ok(version),
+        Err(err) => {
+            fs::write(manifest, original).map_err(|err| io_error(err, manifest))?;
+            Err(err)
+        }
+    }
+}
+
+/// Copies every locked registry package of the package at `manifest` into its vendor directory
+/// The vendor directory is itself a registry, which can be used in place of `registry` from then on.
+///
+/// The packages are copied into a temporary sibling first, so a failed copy leaves the old vendor
+/// directory as it was.
+pub fn vendor(manifest: &Path, registry: &Registry) -> Result<Registry, PackageError> {
+    let root = package_root(manifest);
+    let dest = root.join(VENDOR_DIR);
+    let staging = root.join(format!(".{VENDOR_DIR}.tmp"));
+
+    // vendoring replaces `dest`, so it can't be where the packages are read from
+    let canonical = |path: &Path| fs::canonicalize(path).map_err(|err| io_error(err, path));
+    let vendor_dir = match dest.exists() {
+        true => canonical(&dest)?,
+        false => canonical(root)?.join(VENDOR_DIR),
+    };
+
+    if registry.root().exists() && canonical(registry.root())?.starts_with(&vendor_dir) {
+        return Err(PackageError::new(
+            PackageErrorType::RegistryInVendorDir { vendor: dest },
+            registry.root(),
+        ));
+    }
+
+    let lockfile = lock(manifest, registry)?;
+    remove_dir(&staging)?;
+
+    if let Err(err) = registry.vendor(&lockfile, &staging) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(err);
+    }
+
+    remove_dir(&dest)?;
+    fs::rename(&staging, &dest).map_err(|err| io_error(err, &dest))?;
+
+    Ok(Registry::open(dest))
+}
+
+/// Resolves with `prefer`, checks that every locked package loads, and writes the lockfile
+fn relock(
+    manifest: &Path,
+    registry: &Registry,
+    prefer: &Lockfile,
+) -> Result<Lockfile, PackageError> {
+    let graph = PackageGraph::load(manifest)?;
+    let lockfile = resolve(&graph.requirements(), registry, prefer)?;
+    let _ = PackageGraph::load_locked(manifest, registry, &lockfile)?;
+
+    lockfile.save(&lockfile_path(manifest))?;
+    Ok(lockfile)
+}
+
+/// Removes `dir` and everything in it, if it exists
+fn package_root(manifest: &Path) -> &Path {
+    manifest
+        .parent()
+        .filter(|root| !root.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+}
+
+/// The lockfile of the package whose manifest is at `manifest`
+pub fn lockfile_path(manifest: &Path) -> PathBuf {
+    package_root(manifest).join(LOCKFILE_NAME)
+}
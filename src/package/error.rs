@@ -0,0 +1,150 @@
+//! Defines errors used when loading packages
+use super::lockfile::LOCKFILE_NAME;
+use super::manifest::MANIFEST_NAME;
+use ecow::EcoString;
+use semver::{Version, VersionReq};
+use std::fmt;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackageError {
+    pub error: PackageErrorType,
+    /// The manifest or directory the error was found in
+    pub path: PathBuf,
+}
+
+impl PackageError {
+    pub fn new(err_type: PackageErrorType, path: impl Into<PathBuf>) -> Self {
+        PackageError {
+            error: err_type,
+            path: path.into(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PackageErrorType {
+    ManifestNotFound, // no baros.toml here or in any parent directory
+    Io {
+        kind: ErrorKind,
+    }, // the manifest or a source directory couldn't be read
+    Syntax {
+        message: String,
+    }, // not TOML, or a field is missing or of the wrong type
+    InvalidName {
+        name: EcoString,
+    }, // name = "My Package"
+    MissingEntry {
+        entry: PathBuf,
+    }, // entry = "src/mian.bs"
+    EntryOutsideSources {
+        entry: PathBuf,
+    }, // entry = "scripts/main.bs" with source-dirs = ["src"]
+    MissingSourceDir {
+        dir: PathBuf,
+    }, // source-dirs = ["sauce"]
+    PathOutsidePackage {
+        path: PathBuf,
+    }, // source-dirs = ["../shared"] or entry = "/home/me/main.bs"
+    NameMismatch {
+        expected: EcoString,
+        found: EcoString,
+    }, // util = { path = "../utils" } where that package is named utils
+    DuplicateName {
+        name: EcoString,
+    }, // two different packages with the same name
+    DependencyCycle {
+        cycle: Vec<EcoString>,
+    }, // a depends on b, which depends on a
+    UnknownPackage {
+        name: EcoString,
+    }, // a registry dependency the registry has never heard of
+    NoMatchingVersion {
+        name: EcoString,
+        requirements: Vec<VersionReq>,
+    }, // json = "^2" when only 1.x is published, or ^1 and ^2 at once
+    NotLocked {
+        name: EcoString,
+    }, // a registry dependency missing from baros.lock
+    AlreadyPublished {
+        name: EcoString,
+        version: Version,
+    }, // publishing 1.0.0 twice
+    PathDependency {
+        name: EcoString,
+    }, // publishing a package that depends on util = { path = "../util" }
+    RegistryInVendorDir {
+        vendor: PathBuf,
+    }, // vendoring with BAROS_REGISTRY set to the package's own vendor directory
+    RegistryInSourceDir {
+        dir: PathBuf,
+    }, // publishing with source-dirs = ["."] and BAROS_REGISTRY=./vendor
+}
+
+impl fmt::Display for PackageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.path.display())?;
+
+        match &self.error {
+            PackageErrorType::ManifestNotFound => write!(f, "no {MANIFEST_NAME} here or in any parent directory"),
+            PackageErrorType::Io { kind } => write!(f, "{}", std::io::Error::from(*kind)),
+            PackageErrorType::Syntax { message } => write!(f, "{message}"),
+            PackageErrorType::InvalidName { name } => write!(
+                f,
+                "`{name}` is not a valid package name, which is a lowercase name that isn't a keyword"
+            ),
+            PackageErrorType::MissingEntry { entry } => {
+                write!(f, "the entry point {} does not exist", entry.display())
+            }
+            PackageErrorType::EntryOutsideSources { entry } => {
+                write!(f, "the entry point {} is not in a source directory", entry.display())
+            }
+            PackageErrorType::MissingSourceDir { dir } => {
+                write!(f, "the source directory {} does not exist", dir.display())
+            }
+            PackageErrorType::PathOutsidePackage { path } => write!(
+                f,
+                "{} is not a relative path inside the package",
+                path.display()
+            ),
+            PackageErrorType::NameMismatch { expected, found } => {
+                write!(f, "the dependency `{expected}` points at the package `{found}`")
+            }
+            PackageErrorType::DuplicateName { name } => {
+                write!(f, "more than one package is named `{name}`")
+            }
+            PackageErrorType::DependencyCycle { cycle } => {
+                write!(f, "packages depend on each other: {}", cycle.join(" -> "))
+            }
+            PackageErrorType::UnknownPackage { name } => {
+                write!(f, "the registry has no package named `{name}`")
+            }
+            PackageErrorType::NoMatchingVersion { name, requirements } => {
+                let requirements: Vec<String> = requirements.iter().map(|req| req.to_string()).collect();
+                write!(f, "no published version of `{name}` matches {}", requirements.join(" and "))
+            }
+            PackageErrorType::NotLocked { name } => {
+                write!(f, "`{name}` is not locked to a matching version in {LOCKFILE_NAME}")
+            }
+            PackageErrorType::AlreadyPublished { name, version } => {
+                write!(f, "`{name}` {version} has already been published")
+            }
+            PackageErrorType::PathDependency { name } => {
+                write!(f, "`{name}` is a path dependency, which can't be published")
+            }
+            PackageErrorType::RegistryInVendorDir { vendor } => write!(
+                f,
+                "the registry is in the vendor directory {}, which vendoring replaces",
+                vendor.display()
+            ),
+            PackageErrorType::RegistryInSourceDir { dir } => write!(
+                f,
+                "the registry is in the source directory {}, which publishing copies",
+                dir.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PackageError {}
@@ -0,0 +1,306 @@
+//! Loads a package along with every package it depends on
+use super::error::{PackageError, PackageErrorType};
+use super::lockfile::Lockfile;
+use super::manifest::{Dependency, Manifest, MANIFEST_NAME};
+use super::registry::Registry;
+use semver::VersionReq;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// The extension of Baros source files
+pub const SOURCE_EXTENSION: &str = "bs";
+
+/// Identifies a package in a `PackageGraph`
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct PackageId(u32);
+
+impl PackageId {
+    /// The index of the package within its `PackageGraph`
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A package whose manifest has been loaded and checked against its directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    /// The directory holding the manifest
+    pub root: PathBuf,
+    pub manifest: Manifest,
+    /// The packages this one depends on that were loaded, in the order of its `[dependencies]` table
+    pub dependencies: Vec<PackageId>,
+}
+
+impl Package {
+    pub fn name(&self) -> &str {
+        &self.manifest.package.name
+    }
+
+    /// The path of the entry point
+    pub fn entry(&self) -> PathBuf {
+        self.root.join(&self.manifest.package.entry)
+    }
+
+    /// Finds every source file in the package's source directories, sorted by path
+    pub fn source_files(&self) -> Result<Vec<PathBuf>, PackageError> {
+        let mut files = Vec::new();
+
+        for dir in &self.manifest.package.source_dirs {
+            collect_sources(&self.root.join(dir), &mut files)?;
+        }
+
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+}
+
+/// A package and all of its dependencies, direct or not
+///
+/// Registry dependencies are only followed when the graph is loaded with `load_locked`.
+/// Packages are numbered so that each one comes after everything it depends on,
+/// which makes the order of `packages` an order they can be built in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageGraph {
+    packages: Vec<Package>,
+}
+
+impl PackageGraph {
+    /// Loads the package whose manifest is at `manifest`, then its local dependencies
+    pub fn load(manifest: &Path) -> Result<Self, PackageError> {
+        PackageGraph::load_with(manifest, None)
+    }
+
+    /// Loads the package whose manifest is at `manifest`, then all of its dependencies,
+    /// taking registry packages from `registry` in the versions `lockfile` gives them
+    pub fn load_locked(
+        manifest: &Path,
+        registry: &Registry,
+        lockfile: &Lockfile,
+    ) -> Result<Self, PackageError> {
+        PackageGraph::load_with(manifest, Some((registry, lockfile)))
+    }
+
+    fn load_with(
+        manifest: &Path,
+        locked: Option<(&Registry, &Lockfile)>,
+    ) -> Result<Self, PackageError> {
+        let root = manifest.parent().unwrap_or(Path::new("."));
+        let mut loader = Loader {
+            locked,
+            ..Default::default()
+        };
+        let _ = loader.visit(root)?;
+
+        Ok(PackageGraph {
+            packages: loader.packages,
+        })
+    }
+
+    /// The package that was loaded, rather than depended on
+    pub fn root(&self) -> PackageId {
+        PackageId(self.packages.len() as u32 - 1)
+    }
+
+    pub fn package(&self, id: PackageId) -> &Package {
+        &self.packages[id.index()]
+    }
+
+    /// Iterates over every package, each after its dependencies
+    pub fn packages(&self) -> impl Iterator<Item = (PackageId, &Package)> {
+        self.packages
+            .iter()
+            .enumerate()
+            .map(|(index, package)| (PackageId(index as u32), package))
+    }
+
+    /// Every requirement on a registry package made by a package in the graph
+    pub fn requirements(&self) -> Vec<(String, VersionReq)> {
+        self.packages
+            .iter()
+            .flat_map(|package| &package.manifest.dependencies)
+            .filter_map(|(name, dependency)| Some((name.clone(), dependency.version()?.clone())))
+            .collect()
+    }
+}
+
+/// Walks the dependencies of a package depth first, numbering packages once their dependencies are
+#[derive(Debug, Default)]
+struct Loader<'a> {
+    /// Where registry dependencies are found, if they are followed
+    locked: Option<(&'a Registry, &'a Lockfile)>,
+    packages: Vec<Package>,
+    /// Loaded packages by their canonical root
+    ids: HashMap<PathBuf, PackageId>,
+    /// The canonical root of every loaded package by its name
+    names: HashMap<String, PathBuf>,
+    /// The packages being loaded, each depending on the next
+    stack: Vec<(PathBuf, String)>,
+}
+
+impl Loader<'_> {
+    fn visit(&mut self, root: &Path) -> Result<PackageId, PackageError> {
+        let root = root
+            .canonicalize()
+            .map_err(|err| PackageError::new(PackageErrorType::Io { kind: err.kind() }, root))?;
+
+        if let Some(id) = self.ids.get(&root) {
+            return Ok(*id);
+        }
+
+        let manifest_path = root.join(MANIFEST_NAME);
+
+        if let Some(start) = self
+            .stack
+            .iter()
+            .position(|(on_stack, _)| *on_stack == root)
+        {
+            let cycle = self.stack[start..]
+                .iter()
+                .chain(std::iter::once(&self.stack[start]))
+                .map(|(_, name)| name.as_str().into())
+                .collect();
+
+            return Err(PackageError::new(
+                PackageErrorType::DependencyCycle { cycle },
+                manifest_path,
+            ));
+        }
+
+        let manifest = Manifest::load(&manifest_path)?;
+        let name = manifest.package.name.clone();
+
+        match self.names.get(&name) {
+            Some(other) if *other != root => {
+                return Err(PackageError::new(
+                    PackageErrorType::DuplicateName { name: name.into() },
+                    manifest_path,
+                ));
+            }
+            _ => {
+                let _ = self.names.insert(name.clone(), root.clone());
+            }
+        }
+
+        check_layout(&root, &manifest).map_err(|err| PackageError::new(err, &manifest_path))?;
+
+        self.stack.push((root.clone(), name));
+        let mut dependencies = Vec::with_capacity(manifest.dependencies.len());
+
+        for (name, dependency) in &manifest.dependencies {
+            let dir = match (dependency, self.locked) {
+                (Dependency::Path { path }, _) => root.join(path),
+                (Dependency::Registry { version }, Some((registry, lockfile))) => {
+                    let locked = lockfile
+                        .version(name)
+                        .filter(|locked| version.matches(locked))
+                        .ok_or_else(|| {
+                            PackageError::new(
+                                PackageErrorType::NotLocked { name: name.into() },
+                                &manifest_path,
+                            )
+                        })?;
+
+                    registry.package_dir(name, locked)
+                }
+                (Dependency::Registry { .. }, None) => continue,
+            };
+
+            let id = self.visit(&dir)?;
+            let found = self.packages[id.index()].name();
+
+            if found != name {
+                return Err(PackageError::new(
+                    PackageErrorType::NameMismatch {
+                        expected: name.into(),
+                        found: found.into(),
+                    },
+                    manifest_path,
+                ));
+            }
+
+            dependencies.push(id);
+        }
+
+        let _ = self.stack.pop();
+
+        let id = PackageId(self.packages.len() as u32);
+        let _ = self.ids.insert(root.clone(), id);
+        self.packages.push(Package {
+            root,
+            manifest,
+            dependencies,
+        });
+
+        Ok(id)
+    }
+}
+
+/// Checks that the source directories and entry point of a manifest exist
+/// The entry point has to be in one of the source directories, so that it is a module like any other.
+pub(crate) fn check_layout(root: &Path, manifest: &Manifest) -> Result<(), PackageErrorType> {
+    let package = &manifest.package;
+
+    for path in package.source_dirs.iter().chain([&package.entry]) {
+        if !is_inside_package(path) {
+            return Err(PackageErrorType::PathOutsidePackage { path: path.clone() });
+        }
+    }
+
+    for dir in &package.source_dirs {
+        if !root.join(dir).is_dir() {
+            return Err(PackageErrorType::MissingSourceDir { dir: dir.clone() });
+        }
+    }
+
+    if !root.join(&package.entry).is_file() {
+        return Err(PackageErrorType::MissingEntry {
+            entry: package.entry.clone(),
+        });
+    }
+
+    let entry = without_cur_dir(&package.entry);
+    if !package
+        .source_dirs
+        .iter()
+        .any(|dir| entry.starts_with(without_cur_dir(dir)))
+    {
+        return Err(PackageErrorType::EntryOutsideSources {
+            entry: package.entry.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Whether `path` is relative and never climbs out of the directory it is joined onto
+fn is_inside_package(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+/// `path` without any `.` components, so that `.` and `./src` contain `src/main.bs`
+fn without_cur_dir(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+/// Adds every source file under `dir` to `files`
+fn collect_sources(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), PackageError> {
+    let io_error =
+        |err: std::io::Error| PackageError::new(PackageErrorType::Io { kind: err.kind() }, dir);
+
+    for entry in fs::read_dir(dir).map_err(io_error)? {
+        let path = entry.map_err(io_error)?.path();
+
+        if path.is_dir() {
+            collect_sources(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == SOURCE_EXTENSION) {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
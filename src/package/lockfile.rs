@@ -0,0 +1,81 @@
+//! The `baros.lock` file that records which version of each registry package is used
+use super::error::{PackageError, PackageErrorType};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The file name of the lockfile, which sits next to the manifest
+pub const LOCKFILE_NAME: &str = "baros.lock";
+
+const HEADER: &str =
+    "# Generated by baros when dependencies are resolved; don't edit it by hand.\n\n";
+
+/// The registry packages a package was resolved to, sorted by name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: Version,
+    /// The names of the registry packages this one depends on
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
+}
+
+impl Lockfile {
+    pub fn parse(text: &str) -> Result<Self, PackageErrorType> {
+        toml::from_str(text).map_err(|err| PackageErrorType::Syntax {
+            message: err.message().to_string(),
+        })
+    }
+
+    /// Reads the lockfile at `path`, which is empty if there is no file yet
+    pub fn load(path: &Path) -> Result<Self, PackageError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Lockfile::parse(&text).map_err(|err| PackageError::new(err, path)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Lockfile::default()),
+            Err(err) => Err(PackageError::new(
+                PackageErrorType::Io { kind: err.kind() },
+                path,
+            )),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), PackageError> {
+        fs::write(path, self.to_string())
+            .map_err(|err| PackageError::new(PackageErrorType::Io { kind: err.kind() }, path))
+    }
+
+    /// The locked version of `name`
+    pub fn version(&self, name: &str) -> Option<&Version> {
+        self.packages
+            .iter()
+            .find(|locked| locked.name == name)
+            .map(|locked| &locked.version)
+    }
+
+    /// The lockfile without the packages in `names`, so that they are free to change
+    pub fn unlock(&self, names: &[&str]) -> Lockfile {
+        Lockfile {
+            packages: self
+                .packages
+                .iter()
+                .filter(|locked| !names.contains(&locked.name.as_str()))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for Lockfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let body = toml::to_string(self).map_err(|_| std::fmt::Error)?;
+        write!(f, "{HEADER}{body}")
+    }
+}
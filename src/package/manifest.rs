@@ -0,0 +1,184 @@
+//! The `baros.toml` manifest that describes a package
+use super::error::{PackageError, PackageErrorType};
+use crate::parser::lexer::KEYWORDS;
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The file name every package's manifest has
+pub const MANIFEST_NAME: &str = "baros.toml";
+
+/// A parsed `baros.toml`
+///
+/// ```toml
+/// [package]
+/// name = "app"
+/// version = "0.1.0"
+/// entry = "src/main.bs"
+/// source-dirs = ["src"]
+/// target = "bytecode"
+///
+/// [dependencies]
+/// util = { path = "../util" }
+/// json = "^1.2"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub package: PackageInfo,
+    /// Dependencies by package name
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, Dependency>,
+}
+
+/// The `[package]` table
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PackageInfo {
+    pub name: String,
+    pub version: Version,
+    /// The file the program starts in, relative to the package root
+    #[serde(default = "default_entry")]
+    pub entry: PathBuf,
+    /// The directories holding the package's modules, relative to the package root
+    #[serde(default = "default_source_dirs")]
+    pub source_dirs: Vec<PathBuf>,
+    #[serde(default)]
+    pub target: Target,
+}
+
+/// A dependency on another package
+///
+/// ```toml
+/// [dependencies]
+/// json = "^1.2"
+/// http = { version = "0.3" }
+/// util = { path = "../util" }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawDependency")]
+pub enum Dependency {
+    /// A package from the registry, in any version matching the requirement
+    Registry { version: VersionReq },
+    /// A package in a local directory, given relative to the root of the package depending on it
+    Path { path: PathBuf },
+}
+
+impl Dependency {
+    /// The requirement on the version of a registry package
+    pub fn version(&self) -> Option<&VersionReq> {
+        match self {
+            Dependency::Registry { version } => Some(version),
+            Dependency::Path { .. } => None,
+        }
+    }
+
+    /// The directory of a local package
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Dependency::Registry { .. } => None,
+            Dependency::Path { path } => Some(path),
+        }
+    }
+}
+
+/// A dependency as it is written, which is either a version requirement or a table
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawDependency {
+    Version(VersionReq),
+    Table(DependencyTable),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DependencyTable {
+    version: Option<VersionReq>,
+    path: Option<PathBuf>,
+}
+
+impl TryFrom<RawDependency> for Dependency {
+    type Error = &'static str;
+
+    fn try_from(raw: RawDependency) -> Result<Self, Self::Error> {
+        match raw {
+            RawDependency::Version(version)
+            | RawDependency::Table(DependencyTable {
+                version: Some(version),
+                path: None,
+            }) => Ok(Dependency::Registry { version }),
+            RawDependency::Table(DependencyTable {
+                version: None,
+                path: Some(path),
+            }) => Ok(Dependency::Path { path }),
+            RawDependency::Table(_) => Err("a dependency needs either a `version` or a `path`"),
+        }
+    }
+}
+
+/// The backend a package is compiled for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Target {
+    #[default]
+    Bytecode,
+    Native,
+    Js,
+    C,
+    Wasm,
+}
+
+fn default_entry() -> PathBuf {
+    PathBuf::from("src/main.bs")
+}
+
+fn default_source_dirs() -> Vec<PathBuf> {
+    vec![PathBuf::from("src")]
+}
+
+impl Manifest {
+    /// Parses the text of a manifest, checking the names in it
+    pub fn parse(text: &str) -> Result<Self, PackageErrorType> {
+        let manifest: Manifest = toml::from_str(text).map_err(|err| PackageErrorType::Syntax {
+            message: err.message().to_string(),
+        })?;
+
+        let names = std::iter::once(&manifest.package.name).chain(manifest.dependencies.keys());
+
+        for name in names {
+            if !is_valid_name(name) {
+                return Err(PackageErrorType::InvalidName { name: name.into() });
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Reads and parses the manifest at `path`
+    pub fn load(path: &Path) -> Result<Self, PackageError> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| PackageError::new(PackageErrorType::Io { kind: err.kind() }, path))?;
+
+        Manifest::parse(&text).map_err(|err| PackageError::new(err, path))
+    }
+}
+
+/// Finds the manifest of the package `dir` is in, looking in `dir` and then each of its parents
+pub fn discover(dir: &Path) -> Result<PathBuf, PackageError> {
+    dir.ancestors()
+        .map(|dir| dir.join(MANIFEST_NAME))
+        .find(|path| path.is_file())
+        .ok_or_else(|| PackageError::new(PackageErrorType::ManifestNotFound, dir))
+}
+
+/// Determines if `name` can name a package
+/// Package names are used in imports, so they have to lex as a single lowercase name.
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars.next().is_some_and(|ch| ch.is_ascii_lowercase())
+        && chars.all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_')
+        && !KEYWORDS.iter().any(|(keyword, _)| *keyword == name)
+}
@@ -0,0 +1,276 @@
+//! A registry of published packages kept in a local directory
+//!
+//! The registry is laid out as
+//! * `index/<name>.toml` - Every published version of a package and what it depends on
+//! * `packages/<name>/<version>/` - The files of a published version
+//!
+//! so that resolving dependencies only has to read the index.
+use super::error::{PackageError, PackageErrorType};
+use super::graph::check_layout;
+use super::lockfile::Lockfile;
+use super::manifest::{Manifest, MANIFEST_NAME};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A published version of a package
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub version: Version,
+    /// The registry packages this version depends on
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, VersionReq>,
+}
+
+/// The index file of a package
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Index {
+    #[serde(default)]
+    versions: Vec<IndexEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Registry {
+    root: PathBuf,
+}
+
+impl Registry {
+    /// Uses the registry in `root`, which is created when something is first published to it
+    pub fn open(root: impl Into<PathBuf>) -> Self {
+        Registry { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Every published version of `name`, newest first
+    pub fn versions(&self, name: &str) -> Result<Vec<IndexEntry>, PackageError> {
+        let path = self.index_path(name);
+        let mut index = match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str::<Index>(&text).map_err(|err| {
+                PackageError::new(
+                    PackageErrorType::Syntax {
+                        message: err.message().to_string(),
+                    },
+                    &path,
+                )
+            })?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(PackageError::new(
+                    PackageErrorType::UnknownPackage { name: name.into() },
+                    &self.root,
+                ))
+            }
+            Err(err) => return Err(io_error(err, &path)),
+        };
+
+        index.versions.sort_by(|a, b| b.version.cmp(&a.version));
+        Ok(index.versions)
+    }
+
+    /// The directory the files of a published version are in
+    pub fn package_dir(&self, name: &str, version: &Version) -> PathBuf {
+        self.root
+            .join("packages")
+            .join(name)
+            .join(version.to_string())
+    }
+
+    /// Publishes the package at `root`, copying its manifest and source directories into the registry
+    /// Published packages can only depend on other registry packages.
+    pub fn publish(&self, root: &Path) -> Result<IndexEntry, PackageError> {
+        let manifest_path = root.join(MANIFEST_NAME);
+        let manifest = Manifest::load(&manifest_path)?;
+        check_layout(root, &manifest).map_err(|err| PackageError::new(err, &manifest_path))?;
+
+        let package = &manifest.package;
+        let mut dependencies = BTreeMap::new();
+
+        for (name, dependency) in &manifest.dependencies {
+            match dependency.version() {
+                Some(version) => {
+                    let _ = dependencies.insert(name.clone(), version.clone());
+                }
+                None => {
+                    return Err(PackageError::new(
+                        PackageErrorType::PathDependency { name: name.into() },
+                        manifest_path,
+                    ))
+                }
+            }
+        }
+
+        let mut index = match self.versions(&package.name) {
+            Ok(versions) => Index { versions },
+            Err(PackageError {
+                error: PackageErrorType::UnknownPackage { .. },
+                ..
+            }) => Index::default(),
+            Err(err) => return Err(err),
+        };
+
+        if index
+            .versions
+            .iter()
+            .any(|entry| entry.version == package.version)
+        {
+            return Err(PackageError::new(
+                PackageErrorType::AlreadyPublished {
+                    name: package.name.as_str().into(),
+                    version: package.version.clone(),
+                },
+                &self.root,
+            ));
+        }
+
+        // copying a source directory the registry is in would copy the package into itself
+        let registry_root = resolve_path(&self.root)?;
+        for dir in &package.source_dirs {
+            if registry_root.starts_with(resolve_path(&root.join(dir))?) {
+                return Err(PackageError::new(
+                    PackageErrorType::RegistryInSourceDir { dir: dir.clone() },
+                    manifest_path,
+                ));
+            }
+        }
+
+        // the files are copied next to where they go, so a failed copy leaves nothing behind
+        let dest = self.package_dir(&package.name, &package.version);
+        let staging = dest.with_file_name(format!(".{}.tmp", package.version));
+        remove_dir(&staging)?;
+
+        let copied = copy_file(&manifest_path, &staging.join(MANIFEST_NAME)).and_then(|()| {
+            package
+                .source_dirs
+                .iter()
+                .try_for_each(|dir| copy_dir(&root.join(dir), &staging.join(dir)))
+        });
+
+        if let Err(err) = copied {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(err);
+        }
+
+        remove_dir(&dest)?;
+        fs::rename(&staging, &dest).map_err(|err| io_error(err, &dest))?;
+
+        let entry = IndexEntry {
+            version: package.version.clone(),
+            dependencies,
+        };
+        index.versions.push(entry.clone());
+        self.write_index(&package.name, &index)?;
+
+        Ok(entry)
+    }
+
+    /// Copies the packages in `lockfile` into a new registry in `dest`, which can stand in for this one
+    pub fn vendor(&self, lockfile: &Lockfile, dest: &Path) -> Result<Registry, PackageError> {
+        let vendored = Registry::open(dest);
+        let mut indexes: BTreeMap<&str, Index> = BTreeMap::new();
+
+        for locked in &lockfile.packages {
+            let entry = self
+                .versions(&locked.name)?
+                .into_iter()
+                .find(|entry| entry.version == locked.version)
+                .ok_or_else(|| {
+                    PackageError::new(
+                        PackageErrorType::NoMatchingVersion {
+                            name: locked.name.as_str().into(),
+                            requirements: vec![exact(&locked.version)],
+                        },
+                        &self.root,
+                    )
+                })?;
+
+            copy_dir(
+                &self.package_dir(&locked.name, &locked.version),
+                &vendored.package_dir(&locked.name, &locked.version),
+            )?;
+            indexes
+                .entry(&locked.name)
+                .or_default()
+                .versions
+                .push(entry);
+        }
+
+        for (name, index) in &indexes {
+            vendored.write_index(name, index)?;
+        }
+
+        Ok(vendored)
+    }
+
+    fn index_path(&self, name: &str) -> PathBuf {
+        self.root.join("index").join(format!("{name}.toml"))
+    }
+
+    fn write_index(&self, name: &str, index: &Index) -> Result<(), PackageError> {
+        let path = self.index_path(name);
+        let text = toml::to_string(index).expect("index serializes");
+
+        fs::create_dir_all(self.root.join("index")).map_err(|err| io_error(err, &path))?;
+        fs::write(&path, text).map_err(|err| io_error(err, &path))
+    }
+}
+
+/// A requirement that only `version` matches
+pub(crate) fn exact(version: &Version) -> VersionReq {
+    VersionReq::parse(&format!("={version}")).expect("exact version requirement")
+}
+
+pub(crate) fn io_error(err: io::Error, path: &Path) -> PackageError {
+    PackageError::new(PackageErrorType::Io { kind: err.kind() }, path)
+}
+
+/// Removes `dir` and everything in it, if it exists
+pub(crate) fn remove_dir(dir: &Path) -> Result<(), PackageError> {
+    match fs::remove_dir_all(dir) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(io_error(err, dir)),
+        _ => Ok(()),
+    }
+}
+
+/// Where `path` is, or will be once it is created, with symlinks resolved
+fn resolve_path(path: &Path) -> Result<PathBuf, PackageError> {
+    let path = std::path::absolute(path).map_err(|err| io_error(err, path))?;
+    let existing = path
+        .ancestors()
+        .find(|dir| dir.exists())
+        .expect("the filesystem root exists");
+    let rest = path.strip_prefix(existing).expect("ancestors are prefixes");
+    let existing = fs::canonicalize(existing).map_err(|err| io_error(err, existing))?;
+
+    Ok(existing.join(rest))
+}
+
+fn copy_file(from: &Path, to: &Path) -> Result<(), PackageError> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).map_err(|err| io_error(err, parent))?;
+    }
+
+    let _ = fs::copy(from, to).map_err(|err| io_error(err, from))?;
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), PackageError> {
+    fs::create_dir_all(to).map_err(|err| io_error(err, to))?;
+
+    for entry in fs::read_dir(from).map_err(|err| io_error(err, from))? {
+        let path = entry.map_err(|err| io_error(err, from))?.path();
+        let dest = to.join(path.file_name().expect("directory entries have names"));
+
+        if path.is_dir() {
+            copy_dir(&path, &dest)?;
+        } else {
+            copy_file(&path, &dest)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+//! Picks a version of every registry package that a package needs
+use super::error::{PackageError, PackageErrorType};
+use super::lockfile::{LockedPackage, Lockfile};
+use super::registry::{exact, IndexEntry, Registry};
+use semver::VersionReq;
+use std::collections::{BTreeMap, HashMap};
+
+/// Finds one version of each registry package, directly required or not, that meets every requirement on it
+///
+/// A version locked in `prefer` is tried first if it still meets the requirements,
+/// and otherwise versions are tried from the newest down.
+/// When a choice leads to a conflict further down, the next version is tried instead.
+pub fn resolve(
+    requirements: &[(String, VersionReq)],
+    registry: &Registry,
+    prefer: &Lockfile,
+) -> Result<Lockfile, PackageError> {
+    let mut resolver = Resolver {
+        registry,
+        prefer,
+        versions: HashMap::new(),
+        conflict: None,
+    };
+    let mut chosen = BTreeMap::new();
+
+    if !resolver.solve(&mut chosen, requirements)? {
+        let (name, requirements) = resolver.conflict.expect("failed resolution has a conflict");
+
+        return Err(PackageError::new(
+            PackageErrorType::NoMatchingVersion {
+                name: name.into(),
+                requirements,
+            },
+            registry.root(),
+        ));
+    }
+
+    Ok(Lockfile {
+        packages: chosen
+            .into_iter()
+            .map(|(name, entry)| LockedPackage {
+                name,
+                version: entry.version,
+                dependencies: entry.dependencies.into_keys().collect(),
+            })
+            .collect(),
+    })
+}
+
+struct Resolver<'a> {
+    registry: &'a Registry,
+    prefer: &'a Lockfile,
+    /// The published versions of each package looked at so far, newest first
+    versions: HashMap<String, Vec<IndexEntry>>,
+    /// The package and requirements that most recently couldn't be met
+    conflict: Option<(String, Vec<VersionReq>)>,
+}
+
+impl Resolver<'_> {
+    /// Chooses versions until no requirements are left, returning if that was possible
+    fn solve(
+        &mut self,
+        chosen: &mut BTreeMap<String, IndexEntry>,
+        pending: &[(String, VersionReq)],
+    ) -> Result<bool, PackageError> {
+        let Some(((name, requirement), rest)) = pending.split_first() else {
+            return Ok(true);
+        };
+
+        if let Some(entry) = chosen.get(name) {
+            if requirement.matches(&entry.version) {
+                return self.solve(chosen, rest);
+            }
+
+            let requirements = vec![requirement.clone(), exact(&entry.version)];
+            self.conflict = Some((name.clone(), requirements));
+            return Ok(false);
+        }
+
+        // every requirement on a package stays pending until it is chosen, so they can all be checked at once
+        let requirements: Vec<VersionReq> = pending
+            .iter()
+            .filter(|(other, _)| other == name)
+            .map(|(_, requirement)| requirement.clone())
+            .collect();
+
+        for entry in self.candidates(name)? {
+            if !requirements.iter().all(|req| req.matches(&entry.version)) {
+                continue;
+            }
+
+            let mut next = rest.to_vec();
+            next.extend(entry.dependencies.clone());
+            let _ = chosen.insert(name.clone(), entry);
+
+            if self.solve(chosen, &next)? {
+                return Ok(true);
+            }
+
+            let _ = chosen.remove(name);
+        }
+
+        self.conflict = Some((name.clone(), requirements));
+        Ok(false)
+    }
+
+    /// The versions of `name` in the order they are tried
+    fn candidates(&mut self, name: &str) -> Result<Vec<IndexEntry>, PackageError> {
+        if !self.versions.contains_key(name) {
+            let versions = self.registry.versions(name)?;
+            let _ = self.versions.insert(name.to_string(), versions);
+        }
+
+        let mut candidates = self.versions[name].clone();
+
+        if let Some(locked) = self.prefer.version(name) {
+            if let Some(index) = candidates.iter().position(|entry| entry.version == *locked) {
+                let entry = candidates.remove(index);
+                candidates.insert(0, entry);
+            }
+        }
+
+        Ok(candidates)
+    }
+}
@@ -1,4 +1,9 @@
 pub mod lexer;
 pub mod token;
 pub mod error;
-pub mod comments;
\ No newline at end of file
+pub mod comments;
+pub mod incremental;
+pub mod printer;
+pub mod borrowed;
+pub mod stream;
+pub mod reader;
@@ -0,0 +1,184 @@
+//! A zero-copy lexer for Baros
+//! Runs the same `Lexer`, but rather than copying names, numbers, strings and comments,
+//! tokens hold slices of the source, and names are interned in a symbol table.
+//! Suited to large files, where the copies made by `lexer_from_str` add up.
+use super::error::LexError;
+use super::lexer::{
+    normalize_float, normalize_int, str_to_keyword, ContentKind, Contents, Lexer, NewlineHandler,
+    SpannedToken,
+};
+use super::stream::TokenSource;
+use super::token::Token;
+use crate::util::{Interner, Symbol};
+use std::str::CharIndices;
+
+/// A token that borrows its contents from the source
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BorrowedToken<'src> {
+    Identifier(Symbol),
+    UnusedIdentifier(Symbol),
+    /// The number as written, e.g. `0X1F` or `1_000`
+    Int(&'src str),
+    /// The number as written, e.g. `2.5E+3`
+    Float(&'src str),
+    /// The contents between the quotes, with escapes left as they are
+    Str(&'src str),
+    SingleComment(&'src str),
+    MultiComment(&'src str),
+    DocComment(&'src str),
+    ModComment(&'src str),
+    /// Any token without contents, such as keywords and operators
+    Fixed(Token),
+}
+
+impl BorrowedToken<'_> {
+    /// Converts the token into the `Token` that `Lexer` produces for the same source
+    pub fn to_token(&self, interner: &Interner<'_>) -> Token {
+        match self {
+            BorrowedToken::Identifier(symbol) => Token::Identifier {
+                name: interner.resolve(*symbol).into(),
+            },
+            BorrowedToken::UnusedIdentifier(symbol) => Token::UnusedIdentifier {
+                name: interner.resolve(*symbol).into(),
+            },
+            BorrowedToken::Int(value) => Token::Int {
+                value: normalize_int(value).as_ref().into(),
+            },
+            BorrowedToken::Float(value) => Token::Float {
+                value: normalize_float(value).as_ref().into(),
+            },
+            BorrowedToken::Str(value) => Token::Str {
+                value: normalize_newlines(value).into(),
+            },
+            BorrowedToken::SingleComment(value) => Token::SingleComment {
+                value: value.to_string(),
+            },
+            BorrowedToken::MultiComment(value) => Token::MultiComment {
+                value: normalize_newlines(value),
+            },
+            BorrowedToken::DocComment(value) => Token::DocComment {
+                value: value.to_string(),
+            },
+            BorrowedToken::ModComment(value) => Token::ModComment {
+                value: value.to_string(),
+            },
+            BorrowedToken::Fixed(tok) => tok.clone(),
+        }
+    }
+}
+
+/// Pairs a `BorrowedToken` with its start and end position, like `SpannedToken`
+pub type SpannedBorrowedToken<'src> = (BorrowedToken<'src>, u32, u32);
+pub type BorrowedLexResult<'src> = Result<SpannedBorrowedToken<'src>, LexError>;
+
+/// Create a zero-copy lexer from an inputted string
+pub fn borrowed_lexer_from_str(src: &str) -> BorrowedLexer<'_> {
+    let handler = NewlineHandler::new(SourceChars(src.char_indices()));
+    Lexer::with_contents(handler, Borrowed::new(src))
+}
+
+/// Zero-copy lexer
+pub type BorrowedLexer<'src> = Lexer<NewlineHandler<SourceChars<'src>>, Borrowed<'src>>;
+
+impl<'src> BorrowedLexer<'src> {
+    /// The names interned so far
+    pub fn interner(&self) -> &Interner<'src> {
+        &self.contents().interner
+    }
+
+    /// Consumes the lexer, keeping the names it interned
+    pub fn into_interner(self) -> Interner<'src> {
+        self.into_contents().interner
+    }
+}
+
+impl<'src> TokenSource for BorrowedLexer<'src> {
+    type Token = BorrowedToken<'src>;
+
+    fn next_token(&mut self) -> BorrowedLexResult<'src> {
+        Lexer::next_token(self)
+    }
+
+    fn is_eof(tok: &BorrowedToken<'src>) -> bool {
+        *tok == BorrowedToken::Fixed(Token::Eof)
+    }
+}
+
+/// The characters of a source, with their byte index
+#[derive(Debug, Clone)]
+pub struct SourceChars<'src>(CharIndices<'src>);
+
+impl Iterator for SourceChars<'_> {
+    type Item = (u32, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(i, ch)| (i as u32, ch))
+    }
+}
+
+/// Slices the contents of tokens out of the source, producing `BorrowedToken`s
+#[derive(Debug)]
+pub struct Borrowed<'src> {
+    /// The source being lexed
+    src: &'src str,
+    /// Names seen so far
+    interner: Interner<'src>,
+}
+
+impl<'src> Borrowed<'src> {
+    /// Creates the contents for lexing `src`
+    pub fn new(src: &'src str) -> Self {
+        Borrowed {
+            src,
+            interner: Interner::new(),
+        }
+    }
+}
+
+impl<'src> Contents for Borrowed<'src> {
+    type Token = BorrowedToken<'src>;
+
+    fn fixed(&self, tok: Token) -> BorrowedToken<'src> {
+        BorrowedToken::Fixed(tok)
+    }
+
+    fn begin(&mut self) {}
+
+    fn push(&mut self, _ch: char) {}
+
+    fn finish(&mut self, kind: ContentKind, start: u32, end: u32) -> BorrowedToken<'src> {
+        let src = self.src;
+        let text = &src[start as usize..end as usize];
+
+        match kind {
+            ContentKind::Name => match str_to_keyword(text) {
+                Some(tok) => BorrowedToken::Fixed(tok),
+                None if text.starts_with('_') => {
+                    BorrowedToken::UnusedIdentifier(self.interner.intern(text))
+                }
+                None => BorrowedToken::Identifier(self.interner.intern(text)),
+            },
+            ContentKind::Int => BorrowedToken::Int(text),
+            ContentKind::Float => BorrowedToken::Float(text),
+            ContentKind::Str => BorrowedToken::Str(text),
+            ContentKind::SingleComment => BorrowedToken::SingleComment(text),
+            ContentKind::MultiComment => BorrowedToken::MultiComment(text),
+            ContentKind::DocComment => BorrowedToken::DocComment(text),
+            ContentKind::ModComment => BorrowedToken::ModComment(text),
+        }
+    }
+}
+
+/// Converts a borrowed token into the `SpannedToken` that `Lexer` would have produced
+pub fn to_spanned(tok: &SpannedBorrowedToken<'_>, interner: &Interner<'_>) -> SpannedToken {
+    (tok.0.to_token(interner), tok.1, tok.2)
+}
+
+/// Collapses `\r\n` and `\r` into `\n`, as `NewlineHandler` does
+fn normalize_newlines(value: &str) -> String {
+    if value.contains('\r') {
+        value.replace("\r\n", "\n").replace('\r', "\n")
+    } else {
+        value.to_string()
+    }
+}
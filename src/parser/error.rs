@@ -1,6 +1,7 @@
 //! Defines errors used by the lexer
 use ecow::EcoString;
 use crate::util::Span;
+use std::io::ErrorKind;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct LexError {
@@ -29,6 +30,9 @@ pub enum LexErrorType {
     UnrecognizedToken { tok: char },   // idk
     DisallowedToken { tok: char },     // ; <- we hate these
     BadIdentifier { name: EcoString }, // idk
+    InvalidUtf8,                       // 0xFF, only when lexing from a reader
+    SourceTooLarge,                    // more than u32::MAX bytes of source
+    Io { kind: ErrorKind },            // the reader failed
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
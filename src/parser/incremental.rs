@@ -0,0 +1,187 @@
+//! Incremental relexing of edited source
+//! Rather than lexing a whole file again after every edit, only the tokens around the edit
+//! are relexed, stopping as soon as the lexer lines back up with the previous token stream.
+use super::error::LexError;
+use super::lexer::{source_span, Lexer, NewlineHandler, SpannedToken};
+use super::token::Token;
+use crate::util::Span;
+
+/// A replacement of the text covered by `span` with `text`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    /// The replaced range, in positions of the source before the edit
+    pub span: Span,
+    /// The text inserted in place of `span`
+    pub text: String,
+}
+
+impl TextEdit {
+    pub fn new(span: Span, text: impl Into<String>) -> Self {
+        TextEdit {
+            span,
+            text: text.into(),
+        }
+    }
+
+    /// Applies the edit to the source it was made against
+    pub fn apply(&self, src: &str) -> String {
+        let (start, end) = (self.span.start as usize, self.span.end as usize);
+        let mut out = String::with_capacity(src.len() - (end - start) + self.text.len());
+        out.push_str(&src[..start]);
+        out.push_str(&self.text);
+        out.push_str(&src[end..]);
+        out
+    }
+
+    /// How far everything after the edit is moved by it
+    fn delta(&self) -> i64 {
+        self.text.len() as i64 - (self.span.end - self.span.start) as i64
+    }
+}
+
+/// The change to a token vector caused by a `TextEdit`
+///
+/// The tokens in `start..start + removed` are replaced by `inserted`,
+/// and the positions of every token after them are moved by `delta`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenDiff {
+    pub start: usize,
+    pub removed: usize,
+    pub inserted: Vec<SpannedToken>,
+    pub delta: i64,
+}
+
+impl TokenDiff {
+    /// Applies the diff to the token vector it was computed from
+    pub fn apply(self, tokens: &mut Vec<SpannedToken>) {
+        let end = self.start + self.removed;
+
+        for tok in &mut tokens[end..] {
+            tok.1 = shift(tok.1, self.delta);
+            tok.2 = shift(tok.2, self.delta);
+        }
+
+        let _ = tokens.splice(self.start..end, self.inserted);
+    }
+}
+
+/// Relexes the region of `src` affected by `edit`
+///
+/// * `tokens` - The tokens of the source before the edit, as produced by `lexer_from_str`
+/// * `src` - The source after the edit
+/// * `edit` - The edit that was made
+pub fn relex(tokens: &[SpannedToken], src: &str, edit: &TextEdit) -> Result<TokenDiff, LexError> {
+    let delta = edit.delta();
+    let edit_end = edit.span.start + edit.text.len() as u32;
+
+    // the lexer looks up to two characters past the end of a token,
+    // so relexing starts a token before the first one touching the edit
+    let first = tokens
+        .iter()
+        .position(|tok| tok.2 >= edit.span.start)
+        .unwrap_or(tokens.len());
+    let mut start = first.saturating_sub(1);
+
+    while !is_boundary(tokens, start) {
+        start -= 1;
+    }
+
+    let offset = tokens.get(start).map_or(edit.span.start, |tok| {
+        source_start(tok).min(edit.span.start)
+    });
+    let chars = src[offset as usize..]
+        .char_indices()
+        .map(|(i, ch)| (i as u32 + offset, ch));
+    let mut lexer = Lexer::new(NewlineHandler::new(chars));
+
+    let mut inserted: Vec<SpannedToken> = Vec::new();
+    let mut old = start;
+
+    let mut end = loop {
+        let tok = lexer.next_token()?;
+
+        if tok.0 == Token::Eof {
+            break tokens.len();
+        }
+
+        let tok_start = source_start(&tok);
+        let fresh = inserted
+            .last()
+            .is_none_or(|prev| is_boundary_pair(prev, &tok));
+
+        if tok_start >= edit_end && fresh {
+            // the lexer is back in step if an old token started from the same state here
+            let old_start = tok_start as i64 - delta;
+
+            while old < tokens.len() && (source_start(&tokens[old]) as i64) < old_start {
+                old += 1;
+            }
+
+            if old < tokens.len()
+                && source_start(&tokens[old]) as i64 == old_start
+                && is_boundary(tokens, old)
+            {
+                break old;
+            }
+        }
+
+        inserted.push(tok);
+    };
+
+    // trims the tokens that did not change from either end of the diff
+    let prefix = inserted
+        .iter()
+        .zip(&tokens[start..end])
+        .take_while(|(new, old)| new == old)
+        .count();
+    let _ = inserted.drain(..prefix);
+    start += prefix;
+
+    while end > start
+        && inserted.last().is_some_and(|new| {
+            let old = &tokens[end - 1];
+            new.0 == old.0 && new.1 == shift(old.1, delta) && new.2 == shift(old.2, delta)
+        })
+    {
+        let _ = inserted.pop();
+        end -= 1;
+    }
+
+    Ok(TokenDiff {
+        start,
+        removed: end - start,
+        inserted,
+        delta,
+    })
+}
+
+/// Determines if the lexer starts from a clean state at `tokens[index]`
+fn is_boundary(tokens: &[SpannedToken], index: usize) -> bool {
+    index == 0 || index >= tokens.len() || is_boundary_pair(&tokens[index - 1], &tokens[index])
+}
+
+/// Determines if `tok` was lexed on its own rather than along with `prev`
+/// Names and numbers also lex a directly following minus sign or tuple access.
+fn is_boundary_pair(prev: &SpannedToken, tok: &SpannedToken) -> bool {
+    let joined = matches!(
+        prev.0,
+        Token::Identifier { .. }
+            | Token::UnusedIdentifier { .. }
+            | Token::Int { .. }
+            | Token::Float { .. }
+            | Token::Bool { .. }
+            | Token::Dot
+    ) || prev.0.is_reserved_ident();
+
+    !joined || prev.2 != source_start(tok)
+}
+
+/// The position in the source where a token begins
+fn source_start(tok: &SpannedToken) -> u32 {
+    source_span(tok).start
+}
+
+/// Moves a position by `delta`
+fn shift(pos: u32, delta: i64) -> u32 {
+    (pos as i64 + delta) as u32
+}
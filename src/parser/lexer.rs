@@ -4,6 +4,8 @@
 use super::error::{LexError, LexErrorType, UnicodeEscapeError};
 use super::token::Token;
 use crate::util::Span;
+use std::borrow::Cow;
+use std::collections::VecDeque;
 
 /// Stores variants for the allowed radices in the language
 #[derive(Debug, Clone, Copy)]
@@ -42,12 +44,15 @@ impl Radix {
     }
 }
 /// Lexer
+/// What tokens hold as their contents is up to `C`, which copies them by default.
 #[derive(Debug)]
-pub struct Lexer<T: Iterator<Item = (u32, char)>> {
+pub struct Lexer<T: Iterator<Item = (u32, char)>, C: Contents = Owned> {
     /// Character buffer
     characters: T,
+    /// Produces the contents of tokens
+    contents: C,
     /// Stored tokens
-    queue: Vec<SpannedToken>,
+    queue: VecDeque<LexedToken<C>>,
     /// The current character
     c_char: Option<char>,
     /// The next character
@@ -56,6 +61,8 @@ pub struct Lexer<T: Iterator<Item = (u32, char)>> {
     c_pos: u32,
     /// The next character's index
     n_pos: u32,
+    /// The next character's length in bytes
+    n_len: u32,
     /// The index of the lexer itself
     location: u32,
 }
@@ -68,6 +75,132 @@ pub struct Lexer<T: Iterator<Item = (u32, char)>> {
 pub type SpannedToken = (Token, u32, u32);
 pub type LexResult = Result<SpannedToken, LexError>;
 
+/// Pairs a token produced with `C` with its start and end position, like `SpannedToken`
+pub type LexedToken<C> = (<C as Contents>::Token, u32, u32);
+
+/// The tokens whose contents depend on the source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentKind {
+    /// A keyword, identifier or unused identifier
+    Name,
+    Int,
+    Float,
+    Str,
+    SingleComment,
+    MultiComment,
+    DocComment,
+    ModComment,
+}
+
+/// How a `Lexer` gives names, numbers, strings and comments their contents
+pub trait Contents {
+    /// The tokens the lexer produces
+    type Token: std::fmt::Debug;
+
+    /// Wraps a token without contents, such as a keyword or operator
+    fn fixed(&self, tok: Token) -> Self::Token;
+
+    /// Starts the contents of a token at the current character
+    fn begin(&mut self);
+
+    /// Receives every character the lexer steps over
+    fn push(&mut self, ch: char);
+
+    /// Ends the contents started by `begin`, which cover `start..end` of the source
+    fn finish(&mut self, kind: ContentKind, start: u32, end: u32) -> Self::Token;
+}
+
+/// Copies the contents of tokens out of the characters as they are lexed, producing `Token`s
+#[derive(Debug, Default)]
+pub struct Owned {
+    /// The characters since `begin`
+    buffer: String,
+    /// Whether the lexer is between `begin` and `finish`
+    recording: bool,
+}
+
+impl Contents for Owned {
+    type Token = Token;
+
+    fn fixed(&self, tok: Token) -> Token {
+        tok
+    }
+
+    fn begin(&mut self) {
+        self.buffer.clear();
+        self.recording = true;
+    }
+
+    fn push(&mut self, ch: char) {
+        if self.recording {
+            self.buffer.push(ch);
+        }
+    }
+
+    fn finish(&mut self, kind: ContentKind, _start: u32, _end: u32) -> Token {
+        self.recording = false;
+        let text = self.buffer.as_str();
+
+        match kind {
+            ContentKind::Name => match str_to_keyword(text) {
+                Some(tok) => tok,
+                None if text.starts_with('_') => Token::UnusedIdentifier { name: text.into() },
+                None => Token::Identifier { name: text.into() },
+            },
+            ContentKind::Int => Token::Int {
+                value: normalize_int(text).as_ref().into(),
+            },
+            ContentKind::Float => Token::Float {
+                value: normalize_float(text).as_ref().into(),
+            },
+            ContentKind::Str => Token::Str { value: text.into() },
+            ContentKind::SingleComment => Token::SingleComment { value: text.into() },
+            ContentKind::MultiComment => Token::MultiComment { value: text.into() },
+            ContentKind::DocComment => Token::DocComment { value: text.into() },
+            ContentKind::ModComment => Token::ModComment { value: text.into() },
+        }
+    }
+}
+
+/// Lowercases the radix prefix of an integer as it was written
+pub(crate) fn normalize_int(value: &str) -> Cow<'_, str> {
+    match value.as_bytes() {
+        [b'0', prefix @ (b'X' | b'O' | b'B'), ..] => Cow::Owned(format!(
+            "0{}{}",
+            prefix.to_ascii_lowercase() as char,
+            &value[2..]
+        )),
+        _ => Cow::Borrowed(value),
+    }
+}
+
+/// Lowercases the exponent of a float as it was written, dropping a `+` sign after it
+pub(crate) fn normalize_float(value: &str) -> Cow<'_, str> {
+    match value.find(['e', 'E']) {
+        Some(e) if &value[e..=e] == "E" || value[e + 1..].starts_with('+') => {
+            let exponent = &value[e + 1..];
+            Cow::Owned(format!(
+                "{}e{}",
+                &value[..e],
+                exponent.strip_prefix('+').unwrap_or(exponent)
+            ))
+        }
+        _ => Cow::Borrowed(value),
+    }
+}
+
+/// The span of source text a token was lexed from
+/// Comment tokens start after their opening delimiter, which this includes.
+pub fn source_span(tok: &SpannedToken) -> Span {
+    let delimiter = match tok.0 {
+        Token::SingleComment { .. } | Token::MultiComment { .. } => 2,
+        Token::DocComment { .. } | Token::ModComment { .. } => 3,
+        _ => 0,
+    };
+
+    Span::new(tok.1 - delimiter, tok.2)
+}
+
 /// Create a lexer from an inputted string
 pub fn lexer_from_str(src: &str) -> impl Iterator<Item = LexResult> + '_ {
     let charbuf = src.char_indices().map(|(i, ch)| (i as u32, ch));
@@ -81,13 +214,26 @@ where
 {
     /// Creates a new Lexer from the provided iterator
     pub fn new(input: T) -> Self {
+        Lexer::with_contents(input, Owned::default())
+    }
+}
+
+impl<T, C> Lexer<T, C>
+where
+    T: Iterator<Item = (u32, char)>,
+    C: Contents,
+{
+    /// Creates a new Lexer from the provided iterator, with `contents` producing its tokens
+    pub fn with_contents(input: T, contents: C) -> Self {
         let mut l = Lexer {
             characters: input,
-            queue: Vec::new(),
+            contents,
+            queue: VecDeque::new(),
             c_char: None,
             n_char: None,
             c_pos: 0,
             n_pos: 0,
+            n_len: 0,
             location: 0,
         };
 
@@ -97,6 +243,16 @@ where
         l
     }
 
+    /// What produces the contents of the lexer's tokens
+    pub fn contents(&self) -> &C {
+        &self.contents
+    }
+
+    /// Consumes the lexer, keeping what produced the contents of its tokens
+    pub fn into_contents(self) -> C {
+        self.contents
+    }
+
     /// Retrieve the next character in the buffer
     fn next_char(&mut self) -> Option<char> {
         let current = self.c_char;
@@ -105,28 +261,34 @@ where
                 // triggers everywhere but EOF
                 self.c_pos = self.n_pos;
                 self.n_pos = loc;
+                self.n_len = ch.len_utf8() as u32;
                 Some(ch)
             }
             None => {
-                // triggers at EOF
+                // triggers at EOF, which is just past the last character
                 self.c_pos = self.n_pos;
-                self.n_pos += 1;
+                self.n_pos += self.n_len;
+                self.n_len = 0;
                 None
             }
         };
         self.c_char = self.n_char;
         self.n_char = next;
+
+        if let Some(ch) = current {
+            self.contents.push(ch);
+        }
         current
     }
 
     /// Retrieves the next token
     /// Main entry
-    pub fn next_token(&mut self) -> LexResult {
+    pub fn next_token(&mut self) -> Result<LexedToken<C>, LexError> {
         while self.queue.is_empty() {
             self.decide_next()?;
         }
 
-        Ok(self.queue.remove(0))
+        Ok(self.queue.pop_front().expect("next_token queue"))
     }
 
     /// Looks at the next character and decides what to do
@@ -136,13 +298,13 @@ where
 
             if self.is_name_start(ch) {
                 check_sign = true;
-                let name = self.lex_name()?;
-                self.add_to_queue(name);
+                let name = self.lex_name();
+                self.queue.push_back(name);
                 self.lex_dot_access();
             } else if self.is_number_start(ch, self.n_char) {
                 check_sign = true;
                 let num = self.lex_number()?;
-                self.add_to_queue(num);
+                self.queue.push_back(num);
             } else {
                 self.lex_other(ch)?;
             }
@@ -166,7 +328,7 @@ where
         match ch {
             '"' => {
                 let string = self.lex_string()?;
-                self.add_to_queue(string);
+                self.queue.push_back(string);
             }
             '+' => {
                 let start = self.c_pos;
@@ -209,7 +371,6 @@ where
                                 self.add_to_queue((Token::LongRightArrow, start, end));
                             }
                             _ => {
-                                let _ = self.next_char();
                                 let end = self.c_pos;
                                 return Err(LexError::new(
                                     LexErrorType::UnrecognizedToken { tok: ch },
@@ -260,7 +421,7 @@ where
                     Some('/') => {
                         // handles //, ///, and //!
                         let comment = self.lex_comment();
-                        self.add_to_queue(comment);
+                        self.queue.push_back(comment);
                     }
                     Some('=') => {
                         let _ = self.next_char();
@@ -270,7 +431,7 @@ where
                     Some('*') => {
                         // handles /* AND */
                         let comment = self.lex_comment();
-                        self.add_to_queue(comment);
+                        self.queue.push_back(comment);
                     }
                     _ => {
                         let end = self.c_pos;
@@ -301,11 +462,11 @@ where
                     Some('=') => {
                         let _ = self.next_char();
                         let end = self.c_pos;
-                        self.add_to_queue((Token::StarEq, start, end));
+                        self.add_to_queue((Token::PercentEq, start, end));
                     }
                     _ => {
                         let end = self.c_pos;
-                        self.add_to_queue((Token::Star, start, end));
+                        self.add_to_queue((Token::Percent, start, end));
                     }
                 }
             } // %%, %=, %%=
@@ -322,7 +483,7 @@ where
                     Some('=') => {
                         let _ = self.next_char();
                         let end = self.c_pos;
-                        self.add_to_queue((Token::Eq, start, end));
+                        self.add_to_queue((Token::LessEq, start, end));
                     }
                     Some('<') => {
                         let _ = self.next_char();
@@ -639,6 +800,11 @@ where
             ']' => self.eat_single_char(Token::RightBracket),
             '(' => self.eat_single_char(Token::LeftParen),
             ')' => self.eat_single_char(Token::RightParen),
+            '\n' => self.eat_single_char(Token::Newline),
+            ' ' | '\t' | '\x0C' => {
+                // whitespace is not tokenized, only skipped
+                let _ = self.next_char();
+            }
             ch => {
                 let (start, end) = (self.c_pos, self.c_pos);
                 return Err(LexError::new(
@@ -652,41 +818,41 @@ where
     }
 
     /// Lex identifiers of types, functions, variables, etc.
-    fn lex_name(&mut self) -> LexResult {
-        let mut name = String::new();
+    fn lex_name(&mut self) -> LexedToken<C> {
         let start = self.c_pos;
+        self.contents.begin();
+
+        // the first character has already been checked by is_name_start, and may be '@'
+        let _ = self.next_char().expect("lex_name start");
 
         while self.is_name_continuation() {
-            name.push(self.next_char().expect("lex_name continue"));
+            let _ = self.next_char().expect("lex_name continue");
         }
 
         let end = self.c_pos;
-
-        if let Some(tok) = str_to_keyword(&name) {
-            Ok((tok, start, end))
-        } else if name.starts_with('_') {
-            Ok((Token::UnusedIdentifier { name: name.into() }, start, end))
-        } else {
-            Ok((Token::Identifier { name: name.into() }, start, end))
-        }
+        let tok = self.contents.finish(ContentKind::Name, start, end);
+        (tok, start, end)
     }
 
     /// Function to lex number literals
-    fn lex_number(&mut self) -> LexResult {
+    fn lex_number(&mut self) -> Result<LexedToken<C>, LexError> {
         let start = self.c_pos;
         let number = if self.c_char == Some('0') {
             match self.n_char {
                 Some('x') | Some('X') => {
+                    self.contents.begin();
                     let _ = self.next_char();
                     let _ = self.next_char();
                     self.lex_radix(start, Radix::Hex)?
                 }
                 Some('o') | Some('O') => {
+                    self.contents.begin();
                     let _ = self.next_char();
                     let _ = self.next_char();
                     self.lex_radix(start, Radix::Oct)?
                 }
                 Some('b') | Some('B') => {
+                    self.contents.begin();
                     let _ = self.next_char();
                     let _ = self.next_char();
                     self.lex_radix(start, Radix::Bin)?
@@ -708,96 +874,26 @@ where
     }
 
     /// Function to lex strings
-    fn lex_string(&mut self) -> LexResult {
+    fn lex_string(&mut self) -> Result<LexedToken<C>, LexError> {
         let start = self.c_pos;
         let _ = self.next_char();
-        let mut content = String::new();
+        let content = self.c_pos;
+        self.contents.begin();
+
         loop {
-            match self.next_char() {
+            match self.c_char {
                 Some('\\') => {
-                    let slash_pos = self.c_pos - 1;
+                    let slash_pos = self.c_pos;
+                    let _ = self.next_char();
+
                     if let Some(ch) = self.c_char {
                         match ch {
                             // slash escapes
                             'f' | 'n' | 'r' | 't' | '"' | '\\' => {
                                 let _ = self.next_char();
-                                content.push('\\');
-                                content.push(ch);
                             }
                             // unicode escape
-                            'u' => {
-                                let _ = self.next_char();
-
-                                if self.c_char != Some('{') {
-                                    return Err(LexError::new(
-                                        LexErrorType::UnicodeEscape(
-                                            UnicodeEscapeError::MissingLeftBrace,
-                                        ),
-                                        Span::new(self.c_pos - 1, self.c_pos),
-                                    ));
-                                }
-
-                                // storage for hex digits
-                                let mut digits = String::new();
-
-                                loop {
-                                    let _ = self.next_char();
-
-                                    let Some(ch) = self.c_char else {
-                                        break;
-                                    };
-
-                                    if ch == '}' {
-                                        break;
-                                    }
-
-                                    digits.push(ch);
-
-                                    if !ch.is_ascii_hexdigit() {
-                                        return Err(LexError::new(
-                                            LexErrorType::UnicodeEscape(
-                                                UnicodeEscapeError::ExpectedDigit,
-                                            ),
-                                            Span::new(self.c_pos, self.c_pos + 1),
-                                        ));
-                                    }
-                                }
-
-                                if self.c_char != Some('}') {
-                                    return Err(LexError::new(
-                                        LexErrorType::UnicodeEscape(
-                                            UnicodeEscapeError::ExpectedRightBrace,
-                                        ),
-                                        Span::new(self.c_pos - 1, self.c_pos),
-                                    ));
-                                }
-
-                                let _ = self.next_char();
-
-                                // check that digits contains between 1 and six digits
-                                if !(1..=6).contains(&digits.len()) {
-                                    return Err(LexError::new(
-                                        LexErrorType::UnicodeEscape(
-                                            UnicodeEscapeError::NumberOfDigits,
-                                        ),
-                                        Span::new(slash_pos, self.c_pos),
-                                    ));
-                                }
-
-                                // convert digits to a char
-                                if char::from_u32(
-                                    u32::from_str_radix(&digits, 16).expect(
-                                        "Failed to parse unicode codepoint in escape sequence",
-                                    ),
-                                )
-                                .is_none()
-                                {
-                                    return Err(LexError::new(
-                                        LexErrorType::UnicodeEscape(UnicodeEscapeError::Codepoint),
-                                        Span::new(slash_pos, self.c_pos),
-                                    ));
-                                }
-                            }
+                            'u' => self.lex_unicode_escape(slash_pos)?,
                             _ => {
                                 return Err(LexError::new(
                                     LexErrorType::StringEscape,
@@ -808,7 +904,9 @@ where
                     }
                 }
                 Some('"') => break,
-                Some(ch) => content.push(ch),
+                Some(_) => {
+                    let _ = self.next_char();
+                }
                 None => {
                     return Err(LexError::new(
                         LexErrorType::UnterminatedString,
@@ -818,177 +916,197 @@ where
             }
         }
 
+        let tok = self.contents.finish(ContentKind::Str, content, self.c_pos);
+        let _ = self.next_char();
         let end = self.c_pos;
 
-        let tok = Token::Str {
-            value: content.into(),
-        };
-
         Ok((tok, start, end))
     }
 
+    /// Checks a `\u{...}` escape, starting from the `u`
+    fn lex_unicode_escape(&mut self, slash_pos: u32) -> Result<(), LexError> {
+        let _ = self.next_char();
+
+        if self.c_char != Some('{') {
+            return Err(LexError::new(
+                LexErrorType::UnicodeEscape(UnicodeEscapeError::MissingLeftBrace),
+                Span::new(self.c_pos - 1, self.c_pos),
+            ));
+        }
+
+        // the number of hex digits and the codepoint they spell
+        let mut digits = 0;
+        let mut value: u32 = 0;
+
+        loop {
+            let _ = self.next_char();
+
+            let Some(ch) = self.c_char else {
+                break;
+            };
+
+            if ch == '}' {
+                break;
+            }
+
+            let Some(digit) = ch.to_digit(16) else {
+                return Err(LexError::new(
+                    LexErrorType::UnicodeEscape(UnicodeEscapeError::ExpectedDigit),
+                    Span::new(self.c_pos, self.c_pos + 1),
+                ));
+            };
+
+            digits += 1;
+            value = value.saturating_mul(16).saturating_add(digit);
+        }
+
+        if self.c_char != Some('}') {
+            return Err(LexError::new(
+                LexErrorType::UnicodeEscape(UnicodeEscapeError::ExpectedRightBrace),
+                Span::new(self.c_pos - 1, self.c_pos),
+            ));
+        }
+
+        let _ = self.next_char();
+
+        // check that there are between 1 and six digits
+        if !(1..=6).contains(&digits) {
+            return Err(LexError::new(
+                LexErrorType::UnicodeEscape(UnicodeEscapeError::NumberOfDigits),
+                Span::new(slash_pos, self.c_pos),
+            ));
+        }
+
+        if char::from_u32(value).is_none() {
+            return Err(LexError::new(
+                LexErrorType::UnicodeEscape(UnicodeEscapeError::Codepoint),
+                Span::new(slash_pos, self.c_pos),
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Function to lex comments
     /// Should be able to handle //, ///, //!, and /* */
     /// Entered after 1 slash to accomodate for /* */
-    fn lex_comment(&mut self) -> SpannedToken {
-        enum Kind {
-            Comment,
-            DocComment,
-            MultiComment,
-            ModComment,
-            BadCommentError,
-        }
-
+    fn lex_comment(&mut self) -> LexedToken<C> {
         let kind = match (self.c_char, self.n_char) {
             (Some('/'), Some('!')) => {
                 // mod comment is //!
                 let _ = self.next_char();
                 let _ = self.next_char();
-                Kind::ModComment
+                ContentKind::ModComment
             }
             (Some('/'), Some('/')) => {
                 // doc comment is ///
                 let _ = self.next_char();
                 let _ = self.next_char();
-                Kind::DocComment
+                ContentKind::DocComment
             }
             (Some('/'), _) => {
                 let _ = self.next_char();
-                Kind::Comment
+                ContentKind::SingleComment
             }
             (Some('*'), _) => {
                 let _ = self.next_char();
-                Kind::MultiComment
+                ContentKind::MultiComment
             }
             // This SHOULD be unreachable
-            _ => Kind::BadCommentError,
+            _ => {
+                let pos = self.c_pos;
+                return (self.contents.fixed(Token::Undefined), pos, pos);
+            }
         };
 
-        let mut comment = String::new();
         let start = self.c_pos;
-
-        match kind {
-            Kind::MultiComment => {
-                loop {
-                    if (Some('*'), Some('/')) == (self.c_char, self.n_char) {
-                        // eats the */ and breaks from the loop
-                        let _ = self.next_char();
-                        let _ = self.next_char();
-                        break;
-                    } else if Some('\0') == self.c_char {
-                        // should prevent bad things from happening if comment is not closed
-                        break;
-                    }
-
-                    // consumes the current character and pushes it to `comment`
-                    match self.c_char {
-                        Some(c) => comment.push(c),
-                        None => break,
-                    }
-                    let _ = self.next_char();
+        let mut closed = false;
+        self.contents.begin();
+
+        if kind == ContentKind::MultiComment {
+            loop {
+                if (Some('*'), Some('/')) == (self.c_char, self.n_char) {
+                    closed = true;
+                    break;
+                } else if Some('\0') == self.c_char || self.c_char.is_none() {
+                    // should prevent bad things from happening if comment is not closed
+                    break;
                 }
+
+                let _ = self.next_char();
             }
-            Kind::BadCommentError => {}
-            _ => {
-                while Some('\n') != self.c_char {
-                    match self.c_char {
-                        Some(c) => comment.push(c),
-                        None => break,
-                    }
-                    let _ = self.next_char();
-                }
+        } else {
+            while self.c_char.is_some() && Some('\n') != self.c_char {
+                let _ = self.next_char();
             }
         }
 
-        let end = self.c_pos;
-        let tok = match kind {
-            Kind::Comment => Token::SingleComment { value: comment },
-            Kind::MultiComment => Token::MultiComment { value: comment },
-            Kind::DocComment => Token::DocComment { value: comment },
-            Kind::ModComment => Token::ModComment { value: comment },
-            Kind::BadCommentError => Token::Undefined,
-        };
+        let tok = self.contents.finish(kind, start, self.c_pos);
 
-        (tok, start, end)
+        if closed {
+            // eats the */
+            let _ = self.next_char();
+            let _ = self.next_char();
+        }
+
+        (tok, start, self.c_pos)
     }
 
     /// Can lex both float and integral values
-    fn lex_any_radix(&mut self, can_be_float: bool) -> SpannedToken {
+    fn lex_any_radix(&mut self, can_be_float: bool) -> LexedToken<C> {
         let start = self.c_pos;
-        let mut value = String::new();
+        self.contents.begin();
 
         // Consumes minus
         if self.c_char == Some('-') {
-            value.push(self.next_char().expect("lex_normal negative"))
+            let _ = self.next_char().expect("lex_normal negative");
         }
 
         // Consumes all digits that could occur after a decimal point
-        value.push_str(&self.filter_underscores(Radix::Dec));
+        let _ = self.skip_digits(Radix::Dec);
 
         // Floats
-        if can_be_float && self.c_char == Some('.') {
-            value.push(self.next_char().expect("lex_normal float"));
-            value.push_str(&self.filter_underscores(Radix::Dec));
+        let kind = if can_be_float && self.c_char == Some('.') {
+            let _ = self.next_char().expect("lex_normal float");
+            let _ = self.skip_digits(Radix::Dec);
 
             // Scientific
             if self.c_char == Some('e') || self.c_char == Some('E') {
-                // allows both cases of e but regularize to just lowercase
-                self.c_char = Some('e');
-                value.push(self.next_char().expect("lex_normal scientific"));
-                if self.c_char == Some('-') {
-                    value.push(self.next_char().expect("lex_normal scientific negative"))
-                } else if self.c_char == Some('+') {
-                    // skip over +, allowing you to have syntax like 1E+10 AND 1E10, rather than just 1E10 and 1E-10
-                    let _ = self.next_char();
+                // both cases of e, and 1E+10 as well as 1E10, are normalized by the contents
+                let _ = self.next_char().expect("lex_normal scientific");
+                if self.c_char == Some('-') || self.c_char == Some('+') {
+                    let _ = self.next_char().expect("lex_normal scientific sign");
                 }
+                let _ = self.skip_digits(Radix::Dec);
             }
-            let end = self.c_pos;
 
-            // SpannedToken
-            (
-                Token::Float {
-                    value: value.into(),
-                },
-                start,
-                end,
-            )
+            ContentKind::Float
         } else {
             // occurs when cannot be float or no decimal point is used
-            let end = self.c_pos;
+            ContentKind::Int
+        };
 
-            (
-                Token::Int {
-                    value: value.into(),
-                },
-                start,
-                end,
-            )
-        }
+        let end = self.c_pos;
+        (self.contents.finish(kind, start, end), start, end)
     }
 
-    /// Lexes an integral value of any valid radix
-    fn lex_radix(&mut self, start: u32, radix: Radix) -> LexResult {
-        let number = self.filter_underscores(radix);
+    /// Lexes an integral value of any valid radix, once its prefix has been consumed
+    fn lex_radix(&mut self, start: u32, radix: Radix) -> Result<LexedToken<C>, LexError> {
+        let digits = self.skip_digits(radix);
 
-        if number.is_empty() {
+        if digits == 0 {
             let loc = self.c_pos - 1;
             Err(LexError::new(LexErrorType::NoIntValue, Span::new(loc, loc)))
-        } else if radix.as_num() < 16 && Lexer::<T>::is_valid_digit(self.c_char, radix) {
+        } else if radix.as_num() < 16 && Self::is_valid_digit(self.c_char, Radix::Dec) {
             let loc = self.c_pos;
             Err(LexError::new(
                 LexErrorType::OutOfRadixBounds,
                 Span::new(loc, loc),
             ))
         } else {
-            let value = format!("{}{}", radix.as_prefix(), number);
             let end = self.c_pos;
-            Ok((
-                Token::Int {
-                    value: value.into(),
-                },
-                start,
-                end,
-            ))
+            let tok = self.contents.finish(ContentKind::Int, start, end);
+            Ok((tok, start, end))
         }
     }
 
@@ -998,34 +1116,35 @@ where
             if Some('.') == self.c_char && matches!(self.n_char, Some('0'..='9')) {
                 self.eat_single_char(Token::Dot);
                 let number = self.lex_any_radix(false);
-                self.add_to_queue(number);
+                self.queue.push_back(number);
             } else {
                 break;
             }
         }
     }
 
-    /// Consumes a sequence of numbers within the given radix and removes underscores
-    fn filter_underscores(&mut self, radix: Radix) -> String {
-        let mut value = String::new();
+    /// Consumes a sequence of numbers within the given radix, along with underscores between them
+    /// Returns how many characters were consumed.
+    fn skip_digits(&mut self, radix: Radix) -> usize {
+        let mut count = 0;
 
         loop {
-            if let Some(c) = self.consume_number(radix) {
-                value.push(c);
-            } else if self.c_char == Some('_') && Lexer::<T>::is_valid_digit(self.n_char, radix) {
-                value.push('_');
+            if self.consume_number(radix).is_some() {
+                count += 1;
+            } else if self.c_char == Some('_') && Self::is_valid_digit(self.n_char, radix) {
                 let _ = self.next_char();
+                count += 1;
             } else {
                 break;
             }
         }
 
-        value
+        count
     }
 
     /// Consumes a digit within the given `radix`
     fn consume_number(&mut self, radix: Radix) -> Option<char> {
-        let consume_char = Lexer::<T>::is_valid_digit(self.c_char, radix);
+        let consume_char = Self::is_valid_digit(self.c_char, radix);
 
         if consume_char {
             Some(self.next_char().expect("consume_number next char"))
@@ -1035,13 +1154,9 @@ where
     }
 
     /// Determines if a given digit is a valid digit within the given `radix`
+    /// Radices outside of `2..=36` have no valid digits.
     fn is_valid_digit(ch: Option<char>, radix: Radix) -> bool {
-        match radix {
-            Radix::Bin | Radix::Oct | Radix::Dec | Radix::Hex => {
-                ch.filter(|c| c.is_digit(radix.as_num())).is_some()
-            }
-            other => panic!("The radix {} is not implemented", other.as_num()),
-        }
+        (2..=36).contains(&radix.as_num()) && ch.filter(|c| c.is_digit(radix.as_num())).is_some()
     }
 
     /// Tests a `char` to see if it is a valid start to an identifier or reserved keyword
@@ -1073,25 +1188,31 @@ where
         self.add_to_queue((tok, start, end))
     }
 
-    /// Add a `Token` to the `queue`
-    fn add_to_queue(&mut self, tok: SpannedToken) {
-        self.queue.push(tok);
+    /// Add a `Token` without contents to the `queue`
+    fn add_to_queue(&mut self, (tok, start, end): SpannedToken) {
+        let tok = self.contents.fixed(tok);
+        self.queue.push_back((tok, start, end));
     }
 }
 
-impl<T> Iterator for Lexer<T>
+impl<T, C> Iterator for Lexer<T, C>
 where
     T: Iterator<Item = (u32, char)>,
+    C: Contents,
 {
-    type Item = LexResult;
-    /// advances the lexer to the next token
+    type Item = Result<LexedToken<C>, LexError>;
+    /// advances the lexer to the next token, stopping at the end of the file
     fn next(&mut self) -> Option<Self::Item> {
-        let tok = self.next_token();
+        while self.queue.is_empty() {
+            // end of file and stop, without lexing the `Eof` token
+            self.c_char?;
 
-        match tok {
-            Ok((Token::Eof, _, _)) => None, // end of file and stop
-            c => Some(c),
+            if let Err(err) = self.decide_next() {
+                return Some(Err(err));
+            }
         }
+
+        self.queue.pop_front().map(Ok)
     }
 }
 
@@ -1148,50 +1269,62 @@ where
     }
 }
 
-/// Utility function to turn a `&str` into its keyword
-pub fn str_to_keyword(word: &str) -> Option<Token> {
-    match word {
-        "alias" => Some(Token::Alias),
-        "as" => Some(Token::As),
-        "async" => Some(Token::Async),
-        "await" => Some(Token::Await),
-        "const" => Some(Token::Const),
-        "default" => Some(Token::Default),
-        "do" => Some(Token::Do),
-        "dyn" => Some(Token::Dynamic),
-        "enum" => Some(Token::Enum),
-        "except" => Some(Token::Except),
-        "fun" => Some(Token::Function),
-        "impl" => Some(Token::Impl),
-        "import" => Some(Token::Import),
-        "let" => Some(Token::Let),
-        "mod" => Some(Token::Mod),
-        "mut" => Some(Token::Mut),
-        "pub" => Some(Token::Pub),
-        "return" => Some(Token::Return),
-        "self" => Some(Token::Self_),
-        "static" => Some(Token::Static),
-        "struct" => Some(Token::Struct),
-        "super" => Some(Token::Super),
-        "test" => Some(Token::Test),
-        "trait" => Some(Token::Trait),
-        "try" => Some(Token::Try),
-        "type" => Some(Token::Type),
-        "union" => Some(Token::Union),
-        "use" => Some(Token::Use),
-        "var" => Some(Token::Var),
-        "when" => Some(Token::When),
-        "where" => Some(Token::Where),
-        "if" => Some(Token::If),
-        "elif" => Some(Token::Elif),
-        "else" => Some(Token::Else),
-        "match" => Some(Token::Match),
-        "loop" => Some(Token::Loop),
-        "for" => Some(Token::For),
-        "while" => Some(Token::While),
-        "continue" => Some(Token::Continue),
-        "break" => Some(Token::Break),
-        "in" => Some(Token::In),
-        _ => None,
-    }
+/// Defines `KEYWORDS` and `str_to_keyword` from one table, so that they list the same keywords
+macro_rules! keywords {
+    ($($spelling:literal => $tok:ident,)*) => {
+        /// Every keyword paired with its spelling, for the grammar generators
+        pub const KEYWORDS: &[(&str, Token)] = &[$(($spelling, Token::$tok)),*];
+
+        /// Utility function to turn a `&str` into its keyword
+        pub fn str_to_keyword(word: &str) -> Option<Token> {
+            match word {
+                $($spelling => Some(Token::$tok),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+keywords! {
+    "alias" => Alias,
+    "as" => As,
+    "async" => Async,
+    "await" => Await,
+    "const" => Const,
+    "default" => Default,
+    "do" => Do,
+    "dyn" => Dynamic,
+    "enum" => Enum,
+    "except" => Except,
+    "fun" => Function,
+    "impl" => Impl,
+    "import" => Import,
+    "let" => Let,
+    "mod" => Mod,
+    "mut" => Mut,
+    "pub" => Pub,
+    "return" => Return,
+    "self" => Self_,
+    "static" => Static,
+    "struct" => Struct,
+    "super" => Super,
+    "test" => Test,
+    "trait" => Trait,
+    "try" => Try,
+    "type" => Type,
+    "union" => Union,
+    "use" => Use,
+    "var" => Var,
+    "when" => When,
+    "where" => Where,
+    "if" => If,
+    "elif" => Elif,
+    "else" => Else,
+    "match" => Match,
+    "loop" => Loop,
+    "for" => For,
+    "while" => While,
+    "continue" => Continue,
+    "break" => Break,
+    "in" => In,
 }
@@ -0,0 +1,52 @@
+//! Turns token streams back into source code
+use super::lexer::{source_span, SpannedToken};
+use super::token::Token;
+
+/// Prints tokens separated by single spaces, with no regard for their original layout
+/// Suitable for tokens that were never lexed, like those built by a code generator.
+/// A line comment that is not followed by a newline gets one, as it would swallow the next token otherwise.
+pub fn print_tokens<'a>(tokens: impl IntoIterator<Item = &'a Token>) -> String {
+    let mut out = String::new();
+    let mut line_start = true;
+    let mut open_comment = false;
+
+    for tok in tokens {
+        if open_comment && *tok != Token::Newline {
+            out.push('\n');
+            line_start = true;
+        }
+
+        if !line_start && *tok != Token::Newline {
+            out.push(' ');
+        }
+
+        out.push_str(&tok.to_source());
+        line_start = *tok == Token::Newline;
+        open_comment = matches!(
+            tok,
+            Token::SingleComment { .. } | Token::DocComment { .. } | Token::ModComment { .. }
+        );
+    }
+
+    out
+}
+
+/// Prints lexed tokens, using their spans to keep the original layout
+/// Whitespace between tokens comes back as spaces, and every newline as `\n`.
+pub fn print_spanned(tokens: &[SpannedToken]) -> String {
+    let mut out = String::new();
+    let mut pos = 0;
+
+    for tok in tokens {
+        let span = source_span(tok);
+
+        if span.start > pos {
+            out.extend(std::iter::repeat_n(' ', (span.start - pos) as usize));
+        }
+
+        out.push_str(&tok.0.to_source());
+        pos = pos.max(span.end);
+    }
+
+    out
+}
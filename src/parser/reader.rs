@@ -0,0 +1,214 @@
+//! Lexing from a reader rather than a string held in memory
+//! UTF-8 is decoded as bytes arrive, so the source never has to be read in whole.
+use super::error::{LexError, LexErrorType};
+use super::lexer::{LexResult, Lexer, NewlineHandler};
+use super::stream::TokenSource;
+use super::token::Token;
+use crate::util::Span;
+use std::cell::RefCell;
+use std::io::{BufRead, ErrorKind};
+use std::rc::Rc;
+
+/// Positions are `u32`, and the lexer places the end of the file one past the last character
+const MAX_LEN: u64 = u32::MAX as u64 - 1;
+
+/// Decodes characters from a reader, stopping at the first problem with the source
+/// The problem is stored in `error` for the `ReaderLexer` to report.
+#[derive(Debug)]
+pub struct ReadChars<R: BufRead> {
+    reader: R,
+    /// The number of bytes decoded so far
+    pos: u64,
+    /// How far positions may go, which is `MAX_LEN` outside of tests
+    max_len: u64,
+    error: Rc<RefCell<Option<LexError>>>,
+}
+
+impl<R: BufRead> ReadChars<R> {
+    /// Retrieves the next byte without consuming it
+    fn peek_byte(&mut self) -> Result<Option<u8>, LexError> {
+        loop {
+            match self.reader.fill_buf() {
+                Ok(buf) => return Ok(buf.first().copied()),
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(self.error(LexErrorType::Io { kind: err.kind() }, 0)),
+            }
+        }
+    }
+
+    /// Decodes the character starting at the next byte
+    fn decode(&mut self) -> Result<Option<char>, LexError> {
+        let first = match self.peek_byte()? {
+            Some(byte) => byte,
+            None => return Ok(None),
+        };
+
+        let width = match first {
+            0x00..=0x7F => 1,
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => return Err(self.error(LexErrorType::InvalidUtf8, 1)),
+        };
+
+        if self.pos + width as u64 > self.max_len {
+            return Err(self.error(LexErrorType::SourceTooLarge, 0));
+        }
+
+        let mut bytes = [first, 0, 0, 0];
+        self.reader.consume(1);
+
+        for (read, slot) in (1..).zip(&mut bytes[1..width]) {
+            match self.peek_byte()? {
+                Some(byte @ 0x80..=0xBF) => {
+                    *slot = byte;
+                    self.reader.consume(1);
+                }
+                _ => return Err(self.error(LexErrorType::InvalidUtf8, read)),
+            }
+        }
+
+        // catches overlong encodings and surrogates, which the lead byte alone doesn't rule out
+        match std::str::from_utf8(&bytes[..width]) {
+            Ok(decoded) => Ok(decoded.chars().next()),
+            Err(_) => Err(self.error(LexErrorType::InvalidUtf8, width as u32)),
+        }
+    }
+
+    /// Builds an error covering the next `len` bytes
+    fn error(&self, err_type: LexErrorType, len: u32) -> LexError {
+        let start = self.pos.min(self.max_len) as u32;
+        LexError::new(err_type, Span::new(start, start + len))
+    }
+}
+
+impl<R: BufRead> Iterator for ReadChars<R> {
+    type Item = (u32, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.error.borrow().is_some() {
+            return None;
+        }
+
+        match self.decode() {
+            Ok(Some(ch)) => {
+                let pos = self.pos as u32;
+                self.pos += ch.len_utf8() as u64;
+                Some((pos, ch))
+            }
+            Ok(None) => None,
+            Err(err) => {
+                *self.error.borrow_mut() = Some(err);
+                None
+            }
+        }
+    }
+}
+
+/// Lexer over a reader
+///
+/// Tokens are lexed up to the first invalid byte, read failure,
+/// or the point where positions no longer fit in a `u32`, which is then reported from there on.
+/// Since the lexer looks ahead, tokens and errors running up to the problem are replaced by it too.
+#[derive(Debug)]
+pub struct ReaderLexer<R: BufRead> {
+    lexer: Lexer<NewlineHandler<ReadChars<R>>>,
+    error: Rc<RefCell<Option<LexError>>>,
+}
+
+/// Create a lexer from a buffered reader
+pub fn lexer_from_reader<R: BufRead>(reader: R) -> ReaderLexer<R> {
+    lexer_with_limit(reader, MAX_LEN)
+}
+
+/// Create a lexer that reports `SourceTooLarge` once positions would pass `max_len`
+pub(crate) fn lexer_with_limit<R: BufRead>(reader: R, max_len: u64) -> ReaderLexer<R> {
+    let error = Rc::new(RefCell::new(None));
+    let chars = ReadChars {
+        reader,
+        pos: 0,
+        max_len,
+        error: Rc::clone(&error),
+    };
+
+    ReaderLexer {
+        lexer: Lexer::new(NewlineHandler::new(chars)),
+        error,
+    }
+}
+
+impl<R: BufRead> ReaderLexer<R> {
+    /// Retrieves the next token
+    pub fn next_token(&mut self) -> LexResult {
+        let tok = self.lexer.next_token();
+
+        // the lexer sees the end of the decoded characters as the end of the file,
+        // so a token or error it finds there may have been cut short by the problem with the source
+        match (tok, self.error.borrow().as_ref()) {
+            (tok, None) => tok,
+            (Ok(tok), Some(err)) if tok.0 != Token::Eof && tok.2 < err.location.start => Ok(tok),
+            (_, Some(err)) => Err(err.clone()),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for ReaderLexer<R> {
+    type Item = LexResult;
+    /// advances the lexer to the next token
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_token() {
+            Ok((Token::Eof, _, _)) => None,
+            c => Some(c),
+        }
+    }
+}
+
+impl<R: BufRead> TokenSource for ReaderLexer<R> {
+    type Token = Token;
+
+    fn next_token(&mut self) -> LexResult {
+        ReaderLexer::next_token(self)
+    }
+
+    fn is_eof(tok: &Token) -> bool {
+        *tok == Token::Eof
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lex(src: &str, max_len: u64) -> Vec<LexResult> {
+        lexer_with_limit(src.as_bytes(), max_len).take(3).collect()
+    }
+
+    fn name(name: &str, start: u32, end: u32) -> LexResult {
+        Ok((Token::Identifier { name: name.into() }, start, end))
+    }
+
+    fn too_large(pos: u32) -> LexResult {
+        Err(LexError::new(
+            LexErrorType::SourceTooLarge,
+            Span::new(pos, pos),
+        ))
+    }
+
+    #[test]
+    fn sources_past_the_limit_are_too_large() {
+        assert_eq!(lex("ab c", 4), [name("ab", 0, 2), name("c", 3, 4)]);
+        // `c` ends at the limit too, but the lexer only knows it has ended once it sees `d`
+        assert_eq!(
+            lex("ab cd", 4),
+            [name("ab", 0, 2), too_large(4), too_large(4)]
+        );
+    }
+
+    #[test]
+    fn characters_straddling_the_limit_are_too_large() {
+        assert_eq!(
+            lex("abc\"é\"", 5),
+            [name("abc", 0, 3), too_large(4), too_large(4)]
+        );
+    }
+}
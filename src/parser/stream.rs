@@ -0,0 +1,171 @@
+//! Buffered token stream with lookahead and backtracking
+//! This is the interface the parser consumes tokens through.
+use super::error::LexError;
+use super::lexer::{LexResult, Lexer, NewlineHandler};
+use super::token::Token;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+/// Produces tokens one at a time for a `TokenStream`, such as a `Lexer` or a `ReaderLexer`
+pub trait TokenSource {
+    /// The tokens produced, such as `Token` or `BorrowedToken`
+    type Token: Clone + Debug + PartialEq;
+
+    /// Retrieves the next token, which is the end of the file once the source runs out
+    fn next_token(&mut self) -> Result<SourceToken<Self>, LexError>;
+
+    /// Determines if `tok` is the end of the file
+    fn is_eof(tok: &Self::Token) -> bool;
+}
+
+/// Pairs a token from `S` with its start and end position, like `SpannedToken`
+pub type SourceToken<S> = (<S as TokenSource>::Token, u32, u32);
+pub type SourceResult<S> = Result<SourceToken<S>, LexError>;
+
+impl<T> TokenSource for Lexer<T>
+where
+    T: Iterator<Item = (u32, char)>,
+{
+    type Token = Token;
+
+    fn next_token(&mut self) -> LexResult {
+        Lexer::next_token(self)
+    }
+
+    fn is_eof(tok: &Token) -> bool {
+        *tok == Token::Eof
+    }
+}
+
+/// A position in a `TokenStream` that it can be rewound to
+///
+/// Tokens from a checkpoint onwards are kept until it is passed to either
+/// `TokenStream::rewind` or `TokenStream::commit`.
+#[derive(Debug, PartialEq, Eq)]
+#[must_use = "tokens are kept around until the checkpoint is rewound to or committed"]
+pub struct Checkpoint(usize);
+
+/// Wraps a `TokenSource`, buffering tokens for lookahead and rewinding
+///
+/// The end of the file and lex errors are sticky:
+/// once reached, every further `peek` or `bump` returns them again.
+#[derive(Debug)]
+pub struct TokenStream<S: TokenSource> {
+    lexer: S,
+    /// Tokens that have been lexed but not yet dropped
+    buffer: VecDeque<SourceResult<S>>,
+    /// The index into `buffer` of the next token
+    cursor: usize,
+    /// How many tokens have been dropped from the front of `buffer`
+    dropped: usize,
+    /// The number of checkpoints that are still held
+    checkpoints: usize,
+}
+
+/// Create a token stream from an inputted string
+pub fn token_stream_from_str(src: &str) -> TokenStream<impl TokenSource<Token = Token> + '_> {
+    let charbuf = src.char_indices().map(|(i, ch)| (i as u32, ch));
+    TokenStream::new(Lexer::new(NewlineHandler::new(charbuf)))
+}
+
+impl<S: TokenSource> TokenStream<S> {
+    pub fn new(lexer: S) -> Self {
+        TokenStream {
+            lexer,
+            buffer: VecDeque::new(),
+            cursor: 0,
+            dropped: 0,
+            checkpoints: 0,
+        }
+    }
+
+    /// Looks at the next token without consuming it
+    pub fn peek(&mut self) -> Result<&SourceToken<S>, &LexError> {
+        self.peek_nth(0)
+    }
+
+    /// Looks `k` tokens ahead without consuming anything
+    /// `peek_nth(0)` is the same as `peek()`.
+    pub fn peek_nth(&mut self, k: usize) -> Result<&SourceToken<S>, &LexError> {
+        let index = self.fill(self.cursor + k);
+        self.buffer[index].as_ref()
+    }
+
+    /// Consumes the next token
+    pub fn bump(&mut self) -> SourceResult<S> {
+        let index = self.fill(self.cursor);
+        let result = self.buffer[index].clone();
+
+        if !is_last::<S>(&result) {
+            self.cursor += 1;
+        }
+
+        self.trim();
+        result
+    }
+
+    /// Consumes the next token if it is `tok`
+    pub fn eat(&mut self, tok: &S::Token) -> bool {
+        let found = matches!(self.peek(), Ok((next, _, _)) if next == tok);
+
+        if found {
+            let _ = self.bump();
+        }
+
+        found
+    }
+
+    /// Marks the current position so that the stream can be rewound to it
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        self.checkpoints += 1;
+        Checkpoint(self.dropped + self.cursor)
+    }
+
+    /// Moves the stream back to `checkpoint`, so that its tokens are returned again
+    pub fn rewind(&mut self, checkpoint: Checkpoint) {
+        self.cursor = checkpoint.0 - self.dropped;
+        self.release();
+    }
+
+    /// Keeps everything consumed since `checkpoint`, which can no longer be rewound to
+    pub fn commit(&mut self, _checkpoint: Checkpoint) {
+        self.release();
+    }
+
+    /// Gives up a checkpoint
+    fn release(&mut self) {
+        self.checkpoints -= 1;
+        self.trim();
+    }
+
+    /// Drops the consumed tokens, unless a checkpoint still needs them
+    fn trim(&mut self) {
+        if self.checkpoints == 0 {
+            let _ = self.buffer.drain(..self.cursor);
+            self.dropped += self.cursor;
+            self.cursor = 0;
+        }
+    }
+
+    /// Lexes tokens until `index` is in the buffer or the stream has ended
+    /// Returns the index of the token to use for `index`.
+    fn fill(&mut self, index: usize) -> usize {
+        while self.buffer.len() <= index {
+            if self.buffer.back().is_some_and(is_last::<S>) {
+                return self.buffer.len() - 1;
+            }
+
+            self.buffer.push_back(self.lexer.next_token());
+        }
+
+        index
+    }
+}
+
+/// Determines if nothing can be lexed after `result`
+fn is_last<S: TokenSource>(result: &SourceResult<S>) -> bool {
+    match result {
+        Ok((tok, _, _)) => S::is_eof(tok),
+        Err(_) => true,
+    }
+}
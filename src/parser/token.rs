@@ -153,10 +153,210 @@ pub enum Token {
     LeftPipe,           // <|
 }
 
+/// Broad categories of `Token`s, used for syntax highlighting
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Keyword,
+    Operator,
+    ReservedOperator,
+    Literal,
+    Comment,
+    DocComment,
+    Identifier,
+    UnusedIdentifier,
+    Punctuation,
+    Delimiter,
+    Control,
+}
+
+impl TokenKind {
+    /// All kinds, in the order they are declared
+    pub const ALL: [TokenKind; 11] = [
+        TokenKind::Keyword,
+        TokenKind::Operator,
+        TokenKind::ReservedOperator,
+        TokenKind::Literal,
+        TokenKind::Comment,
+        TokenKind::DocComment,
+        TokenKind::Identifier,
+        TokenKind::UnusedIdentifier,
+        TokenKind::Punctuation,
+        TokenKind::Delimiter,
+        TokenKind::Control,
+    ];
+
+    /// The name of the kind in snake case
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenKind::Keyword => "keyword",
+            TokenKind::Operator => "operator",
+            TokenKind::ReservedOperator => "reserved_operator",
+            TokenKind::Literal => "literal",
+            TokenKind::Comment => "comment",
+            TokenKind::DocComment => "doc_comment",
+            TokenKind::Identifier => "identifier",
+            TokenKind::UnusedIdentifier => "unused_identifier",
+            TokenKind::Punctuation => "punctuation",
+            TokenKind::Delimiter => "delimiter",
+            TokenKind::Control => "control",
+        }
+    }
+}
+
+/// Every token other than keywords that is always spelled the same way
+pub const SYMBOLS: &[Token] = &[
+    Token::Plus,
+    Token::Minus,
+    Token::Star,
+    Token::Slash,
+    Token::Percent,
+    Token::DoubleStar,
+    Token::DoublePercent,
+    Token::LessGreater,
+    Token::Equality,
+    Token::Inequality,
+    Token::Identity,
+    Token::Less,
+    Token::Greater,
+    Token::LessEq,
+    Token::GreaterEq,
+    Token::Amp,
+    Token::Bar,
+    Token::Caret,
+    Token::ShiftLeft,
+    Token::ShiftRight,
+    Token::Eq,
+    Token::PlusEq,
+    Token::MinusEq,
+    Token::StarEq,
+    Token::SlashEq,
+    Token::PercentEq,
+    Token::DoublePercentEq,
+    Token::AmpEq,
+    Token::BarEq,
+    Token::CaretEq,
+    Token::ShiftRightEq,
+    Token::ShiftLeftEq,
+    Token::Range,
+    Token::IncRange,
+    Token::RightRange,
+    Token::LeftRange,
+    Token::Comma,
+    Token::Dot,
+    Token::Colon,
+    Token::SemiColon,
+    Token::DoubleColon,
+    Token::RightArrow,
+    Token::LeftArrow,
+    Token::Exclam,
+    Token::Question,
+    Token::Hash,
+    Token::Dollar,
+    Token::Tilde,
+    Token::At,
+    Token::Underscore,
+    Token::LeftBrace,
+    Token::RightBrace,
+    Token::LeftBracket,
+    Token::RightBracket,
+    Token::LeftParen,
+    Token::RightParen,
+    Token::LessColon,
+    Token::ColonGreater,
+    Token::FatArrow,
+    Token::LongRightArrow,
+    Token::LongLeftArrow,
+    Token::CurlyRightArrow,
+    Token::CurlyLeftArrow,
+    Token::BidirectionalArrow,
+    Token::InvertedRightArrow,
+    Token::InvertedLeftArrow,
+    Token::RightPipe,
+    Token::LeftPipe,
+];
+
 impl Token {
-    /// Determines if the given Token has a reserved keyword
-    pub fn is_reserved_ident(&self) -> bool {
+    /// Classifies the token into its `TokenKind`
+    pub fn kind(&self) -> TokenKind {
         match self {
+            Token::Eof | Token::Newline | Token::Undefined => TokenKind::Control,
+            Token::Identifier { .. } => TokenKind::Identifier,
+            Token::UnusedIdentifier { .. } => TokenKind::UnusedIdentifier,
+            Token::SingleComment { .. } | Token::MultiComment { .. } => TokenKind::Comment,
+            Token::DocComment { .. } | Token::ModComment { .. } => TokenKind::DocComment,
+            Token::Int { .. } | Token::Float { .. } | Token::Str { .. } | Token::Bool { .. } => {
+                TokenKind::Literal
+            }
+            Token::Plus
+            | Token::Minus
+            | Token::Star
+            | Token::Slash
+            | Token::Percent
+            | Token::DoubleStar
+            | Token::DoublePercent
+            | Token::LessGreater
+            | Token::Equality
+            | Token::Inequality
+            | Token::Identity
+            | Token::Less
+            | Token::Greater
+            | Token::LessEq
+            | Token::GreaterEq
+            | Token::Amp
+            | Token::Bar
+            | Token::Caret
+            | Token::ShiftLeft
+            | Token::ShiftRight
+            | Token::Eq
+            | Token::PlusEq
+            | Token::MinusEq
+            | Token::StarEq
+            | Token::SlashEq
+            | Token::PercentEq
+            | Token::DoublePercentEq
+            | Token::AmpEq
+            | Token::BarEq
+            | Token::CaretEq
+            | Token::ShiftRightEq
+            | Token::ShiftLeftEq
+            | Token::Range
+            | Token::IncRange
+            | Token::RightRange
+            | Token::LeftRange => TokenKind::Operator,
+            Token::Comma
+            | Token::Dot
+            | Token::Colon
+            | Token::SemiColon
+            | Token::DoubleColon
+            | Token::RightArrow
+            | Token::LeftArrow
+            | Token::Exclam
+            | Token::Question
+            | Token::Hash
+            | Token::Dollar
+            | Token::Tilde
+            | Token::At
+            | Token::Underscore
+            | Token::OpenMulti
+            | Token::CloseMulti => TokenKind::Punctuation,
+            Token::LeftBrace
+            | Token::RightBrace
+            | Token::LeftBracket
+            | Token::RightBracket
+            | Token::LeftParen
+            | Token::RightParen => TokenKind::Delimiter,
+            Token::LessColon
+            | Token::ColonGreater
+            | Token::FatArrow
+            | Token::LongRightArrow
+            | Token::LongLeftArrow
+            | Token::CurlyRightArrow
+            | Token::CurlyLeftArrow
+            | Token::BidirectionalArrow
+            | Token::InvertedRightArrow
+            | Token::InvertedLeftArrow
+            | Token::RightPipe
+            | Token::LeftPipe => TokenKind::ReservedOperator,
             Token::Alias
             | Token::As
             | Token::Async
@@ -197,11 +397,73 @@ impl Token {
             | Token::While
             | Token::Continue
             | Token::Break
-            | Token::In => true,
+            | Token::In => TokenKind::Keyword,
+        }
+    }
 
-            _ => false,
+    /// Prints the token as source code that lexes back to it
+    /// Unlike `Display`, this keeps the contents of comments and the quotes around strings.
+    /// `Eof` and `Undefined` have no source and print as nothing.
+    pub fn to_source(&self) -> String {
+        match self {
+            Token::Eof | Token::Undefined => String::new(),
+            Token::Newline => "\n".into(),
+            Token::SingleComment { value } => format!("//{value}"),
+            Token::MultiComment { value } => format!("/*{value}*/"),
+            Token::DocComment { value } => format!("///{value}"),
+            Token::ModComment { value } => format!("//!{value}"),
+            Token::Str { value } => format!("\"{value}\""),
+            tok => tok.to_string(),
         }
     }
+
+    /// Determines if the given Token has a reserved keyword
+    pub fn is_reserved_ident(&self) -> bool {
+        matches!(
+            self,
+            Token::Alias
+                | Token::As
+                | Token::Async
+                | Token::Await
+                | Token::Const
+                | Token::Default
+                | Token::Do
+                | Token::Dynamic
+                | Token::Enum
+                | Token::Except
+                | Token::Function
+                | Token::Impl
+                | Token::Import
+                | Token::Let
+                | Token::Mod
+                | Token::Mut
+                | Token::Pub
+                | Token::Return
+                | Token::Self_
+                | Token::Static
+                | Token::Struct
+                | Token::Super
+                | Token::Test
+                | Token::Trait
+                | Token::Try
+                | Token::Type
+                | Token::Union
+                | Token::Use
+                | Token::Var
+                | Token::When
+                | Token::Where
+                | Token::If
+                | Token::Elif
+                | Token::Else
+                | Token::Match
+                | Token::Loop
+                | Token::For
+                | Token::While
+                | Token::Continue
+                | Token::Break
+                | Token::In
+        )
+    }
 }
 
 impl std::fmt::Display for Token {
@@ -0,0 +1,200 @@
+//! Keeps the text of every source file, so that file-qualified spans can be traced back to it
+use crate::parser::error::LexError;
+use crate::parser::lexer::{lexer_from_str, source_span, SpannedToken};
+use crate::util::{FileId, Span};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// A file stored in a `SourceDatabase`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFile {
+    path: PathBuf,
+    text: String,
+    /// The position each line starts at
+    line_starts: Vec<u32>,
+}
+
+impl SourceFile {
+    fn new(path: PathBuf, text: String) -> Self {
+        let line_starts = line_starts(&text);
+
+        SourceFile {
+            path,
+            text,
+            line_starts,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The line and column of `pos`, both counted from 1
+    /// Columns count characters rather than bytes, so `pos` has to be on a character boundary.
+    pub fn line_col(&self, pos: u32) -> (u32, u32) {
+        let line = self.line_starts.partition_point(|start| *start <= pos) - 1;
+        let start = self.line_starts[line] as usize;
+        let end = (pos as usize).min(self.text.len());
+        let column = self.text[start..end].chars().count();
+
+        (line as u32 + 1, column as u32 + 1)
+    }
+}
+
+/// Where a span starts, for showing to users
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location<'a> {
+    pub path: &'a Path,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl fmt::Display for Location<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.path.display(), self.line, self.column)
+    }
+}
+
+/// Assigns each source file a `FileId` and stores its path and text
+#[derive(Debug, Default, Clone)]
+pub struct SourceDatabase {
+    files: Vec<SourceFile>,
+    ids: HashMap<PathBuf, FileId>,
+}
+
+impl SourceDatabase {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a file, or replaces the text of the file already stored at `path`
+    pub fn add(&mut self, path: impl Into<PathBuf>, text: impl Into<String>) -> FileId {
+        let path = path.into();
+
+        match self.ids.get(&path) {
+            Some(id) => {
+                let id = *id;
+                self.set_text(id, text);
+                id
+            }
+            None => {
+                let id = FileId::new(self.files.len());
+                let _ = self.ids.insert(path.clone(), id);
+                self.files.push(SourceFile::new(path, text.into()));
+                id
+            }
+        }
+    }
+
+    /// Reads the file at `path` from disk and adds it
+    pub fn load(&mut self, path: impl AsRef<Path>) -> io::Result<FileId> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        Ok(self.add(path, text))
+    }
+
+    /// Replaces the text of `file`, such as after it was edited
+    ///
+    /// # Panics
+    /// If `file` is not in this database
+    pub fn set_text(&mut self, file: FileId, text: impl Into<String>) {
+        let source = &mut self.files[file.index()];
+        *source = SourceFile::new(std::mem::take(&mut source.path), text.into());
+    }
+
+    /// Retrieves a stored file
+    pub fn file(&self, file: FileId) -> Option<&SourceFile> {
+        self.files.get(file.index())
+    }
+
+    /// Finds the `FileId` of the file stored at `path`
+    pub fn file_id(&self, path: impl AsRef<Path>) -> Option<FileId> {
+        self.ids.get(path.as_ref()).copied()
+    }
+
+    /// Iterates over every stored file in the order they were added
+    pub fn files(&self) -> impl Iterator<Item = (FileId, &SourceFile)> {
+        self.files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| (FileId::new(index), file))
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// The text covered by `span`
+    pub fn snippet(&self, span: Span) -> Option<&str> {
+        self.file(span.file)?
+            .text
+            .get(span.start as usize..span.end as usize)
+    }
+
+    /// Where `span` starts
+    pub fn location(&self, span: Span) -> Option<Location<'_>> {
+        let file = self.file(span.file)?;
+        let (line, column) = file.line_col(span.start);
+
+        Some(Location {
+            path: &file.path,
+            line,
+            column,
+        })
+    }
+
+    /// Lexes `file` up to the end of the file, returning the first error in the file if there is one
+    ///
+    /// # Panics
+    /// If `file` is not in this database
+    pub fn lex(&self, file: FileId) -> Result<Vec<SpannedToken>, LexError> {
+        let mut tokens = Vec::new();
+
+        for result in lexer_from_str(&self.files[file.index()].text) {
+            match result {
+                Ok(tok) => tokens.push(tok),
+                Err(mut err) => {
+                    err.location = err.location.with_file(file);
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(tokens)
+    }
+}
+
+/// The span of source text a token in `file` was lexed from
+pub fn token_span(file: FileId, tok: &SpannedToken) -> Span {
+    source_span(tok).with_file(file)
+}
+
+/// Finds where every line of `text` starts, treating `\r\n` and a lone `\r` as one line break like the lexer does
+fn line_starts(text: &str) -> Vec<u32> {
+    let bytes = text.as_bytes();
+    let mut starts = vec![0];
+
+    for (i, byte) in bytes.iter().enumerate() {
+        let ends_line = match byte {
+            b'\n' => true,
+            b'\r' => bytes.get(i + 1) != Some(&b'\n'),
+            _ => false,
+        };
+
+        if ends_line {
+            starts.push(i as u32 + 1);
+        }
+    }
+
+    starts
+}
@@ -1,13 +1,131 @@
 //! Contains a variety of helpful things that are used throughout the project
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
 
+/// Identifies a file in a `SourceDatabase`
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct FileId(u32);
+
+impl FileId {
+    /// The file of a span made without knowing which file it is in, like those from the lexer
+    pub const UNKNOWN: FileId = FileId(u32::MAX);
+
+    pub(crate) fn new(index: usize) -> Self {
+        FileId(index as u32)
+    }
+
+    /// The index of the file within its `SourceDatabase`
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Default for FileId {
+    fn default() -> Self {
+        FileId::UNKNOWN
+    }
+}
+
+/// A range of bytes in a source file
+/// Only errors carry spans so far. Lexer tokens are still bare `(Token, u32, u32)` triples, so the
+/// file they came from has to be tracked alongside them, as `source::token_span` does.
 #[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
 pub struct Span {
+    pub file: FileId,
     pub start: u32,
     pub end: u32,
 }
 
 impl Span {
+    /// Creates a span in an unknown file
     pub fn new(start: u32, end: u32) -> Self {
-        Span { start, end }
+        Span {
+            file: FileId::UNKNOWN,
+            start,
+            end,
+        }
+    }
+
+    /// Creates a span in `file`
+    pub fn in_file(file: FileId, start: u32, end: u32) -> Self {
+        Span { file, start, end }
+    }
+
+    /// Moves the span into `file`, keeping its positions
+    pub fn with_file(self, file: FileId) -> Self {
+        Span { file, ..self }
+    }
+}
+
+/// An identifier interned in an `Interner`
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// The index of the symbol within its `Interner`
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A symbol table that stores each distinct name once, borrowing it from the source
+#[derive(Debug, Default, Clone)]
+pub struct Interner<'src> {
+    names: Vec<&'src str>,
+    lookup: HashMap<&'src str, Symbol, BuildHasherDefault<FnvHasher>>,
+}
+
+impl<'src> Interner<'src> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Retrieves the `Symbol` for `name`, adding it if it is new
+    pub fn intern(&mut self, name: &'src str) -> Symbol {
+        if let Some(symbol) = self.lookup.get(name) {
+            return *symbol;
+        }
+
+        let symbol = Symbol(self.names.len() as u32);
+        self.names.push(name);
+        let _ = self.lookup.insert(name, symbol);
+        symbol
+    }
+
+    /// Retrieves the name a `Symbol` stands for
+    pub fn resolve(&self, symbol: Symbol) -> &'src str {
+        self.names[symbol.index()]
+    }
+
+    /// The number of distinct names
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
     }
-}
\ No newline at end of file
+}
+
+/// FNV-1a, which hashes short keys like identifiers much faster than the default hasher
+#[derive(Debug, Clone, Copy)]
+pub struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
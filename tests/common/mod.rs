@@ -0,0 +1,47 @@
+//! Fixtures shared by the package and registry tests
+use baros::package::manifest::MANIFEST_NAME;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// A fresh directory for a test to lay packages out in, removed when it is dropped
+pub struct Scratch(PathBuf);
+
+impl Scratch {
+    pub fn new(test: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("baros-{}-{test}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("create scratch directory");
+        Scratch(dir)
+    }
+}
+
+impl Deref for Scratch {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Writes a package to `dir/<name>-<version>` whose `[dependencies]` table is `dependencies`,
+/// returning its manifest
+/// Its sources are a `src/main.bs` entry point holding the package's name and `src/nested/util.bs`.
+pub fn package(dir: &Path, name: &str, version: &str, dependencies: &str) -> PathBuf {
+    let root = dir.join(format!("{name}-{version}"));
+    let manifest = format!(
+        "# {name}\n[package]\nname = \"{name}\"\nversion = \"{version}\"\n\n[dependencies]\n{dependencies}"
+    );
+
+    fs::create_dir_all(root.join("src/nested")).expect("create package");
+    fs::write(root.join("src/main.bs"), name).expect("write entry");
+    fs::write(root.join("src/nested/util.bs"), "util").expect("write module");
+    fs::write(root.join(MANIFEST_NAME), manifest).expect("write manifest");
+    root.join(MANIFEST_NAME)
+}
@@ -0,0 +1,38 @@
+//! Checks that the generated grammars checked into the repository are up to date
+//! Run with `BAROS_UPDATE_SNAPSHOTS=1` to regenerate them.
+use baros::highlight::{textmate, tree_sitter};
+use std::fs;
+use std::path::Path;
+
+fn check(relative: &str, generated: String) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(relative);
+
+    if std::env::var_os("BAROS_UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, generated).expect("write generated grammar");
+        return;
+    }
+
+    let checked_in = fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        checked_in == generated,
+        "{relative} is out of date\nrerun with BAROS_UPDATE_SNAPSHOTS=1 to regenerate it"
+    );
+}
+
+#[test]
+fn tree_sitter_grammar() {
+    check("tree-sitter-baros/grammar.js", tree_sitter::grammar());
+}
+
+#[test]
+fn tree_sitter_highlights() {
+    check(
+        "tree-sitter-baros/queries/highlights.scm",
+        tree_sitter::highlights(),
+    );
+}
+
+#[test]
+fn textmate_grammar() {
+    check("textmate-baros/baros.tmLanguage.json", textmate::grammar());
+}
@@ -0,0 +1,108 @@
+//! Tests for token kinds, highlighting, and the TextMate grammar built from them
+use baros::highlight::{ansi, html, segments, textmate};
+use baros::parser::lexer::{lexer_from_str, KEYWORDS};
+use baros::parser::token::{Token, TokenKind, SYMBOLS};
+
+#[test]
+fn only_keywords_are_keywords() {
+    for (spelling, tok) in KEYWORDS {
+        assert_eq!(tok.kind(), TokenKind::Keyword, "{spelling}");
+    }
+
+    for tok in SYMBOLS {
+        assert_ne!(tok.kind(), TokenKind::Keyword, "{tok}");
+    }
+
+    let others = [
+        Token::Identifier { name: "a".into() },
+        Token::UnusedIdentifier { name: "_a".into() },
+        Token::Bool {
+            value: "true".into(),
+        },
+        Token::Newline,
+        Token::Eof,
+    ];
+    for tok in others {
+        assert_ne!(tok.kind(), TokenKind::Keyword, "{tok:?}");
+    }
+}
+
+#[test]
+fn grammar_symbols_are_lexed_as_themselves() {
+    for kind in TokenKind::ALL {
+        for symbol in textmate::symbols(kind) {
+            let tokens: Vec<_> = lexer_from_str(&symbol).collect();
+
+            match &tokens[..] {
+                [Ok((tok, _, _))] => {
+                    assert_eq!(tok.to_string(), symbol);
+                    assert_eq!(tok.kind(), kind, "{symbol}");
+                }
+                tokens => panic!("{symbol} lexes as {tokens:?}"),
+            }
+        }
+    }
+}
+
+#[test]
+fn segments_cover_the_source() {
+    let src = "let x = 1 // one\n_y";
+
+    assert_eq!(
+        segments(src),
+        [
+            ("let", Some(TokenKind::Keyword)),
+            (" ", None),
+            ("x", Some(TokenKind::Identifier)),
+            (" ", None),
+            ("=", Some(TokenKind::Operator)),
+            (" ", None),
+            ("1", Some(TokenKind::Literal)),
+            (" ", None),
+            ("// one", Some(TokenKind::Comment)),
+            ("\n", Some(TokenKind::Control)),
+            ("_y", Some(TokenKind::UnusedIdentifier)),
+        ]
+    );
+
+    // nothing after an error has a kind
+    assert_eq!(
+        segments("a; b"),
+        [("a", Some(TokenKind::Identifier)), ("; b", None)]
+    );
+}
+
+#[test]
+fn segments_run_to_the_end_of_the_file() {
+    assert_eq!(segments("// é"), [("// é", Some(TokenKind::Comment))]);
+    assert_eq!(
+        segments("a /* 日本"),
+        [
+            ("a", Some(TokenKind::Identifier)),
+            (" ", None),
+            ("/* 日本", Some(TokenKind::Comment)),
+        ]
+    );
+    assert_eq!(
+        html::highlight("/// 日本"),
+        "<pre class=\"baros\"><span class=\"bs-doc_comment\">/// 日本</span></pre>"
+    );
+}
+
+#[test]
+fn ansi_output() {
+    assert_eq!(
+        ansi::highlight("fun f(x)"),
+        "\x1b[35mfun\x1b[0m f\x1b[1m(\x1b[0mx\x1b[1m)\x1b[0m"
+    );
+}
+
+#[test]
+fn html_output() {
+    assert_eq!(
+        html::highlight("a < \"&\"\n"),
+        "<pre class=\"baros\"><span class=\"bs-identifier\">a</span> \
+         <span class=\"bs-operator\">&lt;</span> \
+         <span class=\"bs-literal\">&quot;&amp;&quot;</span>\n</pre>"
+    );
+}
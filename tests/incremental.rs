@@ -0,0 +1,57 @@
+//! Tests for relexing only the part of a source touched by an edit
+use baros::parser::incremental::{relex, TextEdit};
+use baros::parser::lexer::{lexer_from_str, SpannedToken};
+use baros::util::Span;
+
+fn lex(src: &str) -> Vec<SpannedToken> {
+    lexer_from_str(src)
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|err| panic!("{src:?} does not lex: {err:?}"))
+}
+
+/// Replaces `start..end` of `src` with `text`, checking the relexed tokens against a full relex
+/// Returns how many tokens the diff replaced and inserted.
+fn edit(src: &str, start: u32, end: u32, text: &str) -> (usize, usize) {
+    let edit = TextEdit::new(Span::new(start, end), text);
+    let edited = edit.apply(src);
+    let mut tokens = lex(src);
+
+    let diff = relex(&tokens, &edited, &edit).unwrap();
+    let counts = (diff.removed, diff.inserted.len());
+    diff.apply(&mut tokens);
+
+    assert_eq!(tokens, lex(&edited), "{src:?} edited to {edited:?}");
+    counts
+}
+
+#[test]
+fn opening_a_string() {
+    assert_eq!(edit("a b // \"\nc", 0, 0, "\""), (3, 1));
+}
+
+#[test]
+fn closing_a_comment() {
+    // the old `*/` is left over as a `*` and a `/`
+    assert_eq!(edit("/* a b */ c", 4, 4, " */"), (1, 4));
+}
+
+#[test]
+fn spacing_out_a_negative_number() {
+    // `a-1` is a subtraction, `a -1` a name followed by a negative number
+    assert_eq!(edit("a-1", 1, 1, " "), (2, 1));
+    assert_eq!(edit("a -1", 1, 2, ""), (1, 2));
+}
+
+#[test]
+fn edits_at_the_end_of_the_file() {
+    assert_eq!(edit("let a", 5, 5, "b"), (1, 1));
+    assert_eq!(edit("let a", 5, 5, " = 1\n"), (0, 3));
+    assert_eq!(edit("let a = 1", 5, 9, ""), (2, 0));
+    assert_eq!(edit("", 0, 0, "a"), (0, 1));
+}
+
+#[test]
+fn edits_far_from_the_end_stop_early() {
+    let src = "let a = 1\nlet b = 2\nlet c = 3\n";
+    assert_eq!(edit(src, 4, 5, "x"), (1, 1));
+}
@@ -0,0 +1,21 @@
+//! Tests that the keyword table the grammars are generated from agrees with the lexer
+use baros::parser::lexer::{str_to_keyword, KEYWORDS};
+use std::collections::HashSet;
+
+#[test]
+fn keywords_round_trip() {
+    for (spelling, tok) in KEYWORDS {
+        assert_eq!(str_to_keyword(spelling).as_ref(), Some(tok), "{spelling}");
+        assert_eq!(tok.to_string(), *spelling);
+    }
+
+    let spellings: HashSet<_> = KEYWORDS.iter().map(|(spelling, _)| spelling).collect();
+    assert_eq!(spellings.len(), KEYWORDS.len(), "keywords are listed once");
+}
+
+#[test]
+fn other_names_are_not_keywords() {
+    for name in ["Let", "lets", "el", "self_", "@if", "_", ""] {
+        assert_eq!(str_to_keyword(name), None, "{name}");
+    }
+}
@@ -0,0 +1,275 @@
+//! Property tests for the lexer
+//! Inputs are either arbitrary text or text built out of token-shaped fragments.
+use baros::parser::borrowed::{borrowed_lexer_from_str, to_spanned};
+use baros::parser::error::LexError;
+use baros::parser::incremental::{relex, TextEdit};
+use baros::parser::lexer::{lexer_from_str, source_span, SpannedToken, KEYWORDS};
+use baros::parser::printer::{print_spanned, print_tokens};
+use baros::parser::reader::lexer_from_reader;
+use baros::parser::token::{Token, TokenKind, SYMBOLS};
+use baros::util::Span;
+use proptest::prelude::*;
+use std::io::BufReader;
+
+/// Lexes `src` up to the end of the file or the first error
+fn lex(src: &str) -> Vec<SpannedToken> {
+    lex_with_error(src).0
+}
+
+/// Lexes `src` up to the end of the file or the first error, returning the error too
+fn lex_with_error(src: &str) -> (Vec<SpannedToken>, Option<LexError>) {
+    // every token consumes at least one character, so more tokens than that means the lexer is stuck
+    let limit = src.chars().count() + 1;
+    let mut tokens = Vec::new();
+
+    for result in lexer_from_str(src).take(limit + 1) {
+        match result {
+            Ok(tok) => tokens.push(tok),
+            Err(err) => return (tokens, Some(err)),
+        }
+    }
+
+    assert!(tokens.len() <= limit, "lexer made no progress on {src:?}");
+    (tokens, None)
+}
+
+/// Lexes `src` with the zero-copy lexer, converting its tokens into owned ones
+fn lex_borrowed(src: &str) -> (Vec<SpannedToken>, Option<LexError>) {
+    let limit = src.chars().count() + 1;
+    let mut lexer = borrowed_lexer_from_str(src);
+    let mut tokens = Vec::new();
+
+    for _ in 0..=limit {
+        match lexer.next() {
+            Some(Ok(tok)) => tokens.push(to_spanned(&tok, lexer.interner())),
+            Some(Err(err)) => return (tokens, Some(err)),
+            None => break,
+        }
+    }
+
+    assert!(
+        tokens.len() <= limit,
+        "zero-copy lexer made no progress on {src:?}"
+    );
+    (tokens, None)
+}
+
+/// Lexes `src` through a reader that hands over `capacity` bytes at a time
+fn lex_reader(src: &str, capacity: usize) -> (Vec<SpannedToken>, Option<LexError>) {
+    let limit = src.chars().count() + 1;
+    let reader = BufReader::with_capacity(capacity, src.as_bytes());
+    let mut tokens = Vec::new();
+
+    for result in lexer_from_reader(reader).take(limit + 1) {
+        match result {
+            Ok(tok) => tokens.push(tok),
+            Err(err) => return (tokens, Some(err)),
+        }
+    }
+
+    assert!(
+        tokens.len() <= limit,
+        "reader lexer made no progress on {src:?}"
+    );
+    (tokens, None)
+}
+
+/// Fragments that lex to one or more tokens
+fn fragment() -> impl Strategy<Value = String> {
+    let fixed: Vec<String> = KEYWORDS
+        .iter()
+        .map(|(spelling, _)| spelling.to_string())
+        .chain(SYMBOLS.iter().map(|tok| tok.to_string()))
+        .collect();
+
+    prop_oneof![
+        4 => proptest::sample::select(fixed),
+        2 => "[-+*/%<>=&|^.:!?#$~(){},;@_\\[\\]\"]{1,3}",
+        2 => "[@_a-zA-Z][_a-zA-Z0-9]{0,6}",
+        2 => "-?[0-9][0-9_]{0,4}(\\.[0-9]{0,3}([eE][+-]?[0-9]{1,2})?)?",
+        1 => "0[xXoObB][0-9a-fA-F_]{0,4}",
+        1 => "\"([a-z ]|\\\\[fnrt\"\\\\]|\\\\u\\{[0-9a-fA-F]{1,6}\\})*\"",
+        1 => "//[/!]?[a-z ]{0,8}\n",
+        1 => "/\\*[a-z \n]{0,8}\\*/",
+        1 => "[ \t\r\n]",
+    ]
+}
+
+/// Source built out of fragments, sometimes separated by a space
+fn token_shaped() -> impl Strategy<Value = String> {
+    prop::collection::vec((fragment(), any::<bool>()), 0..24).prop_map(|parts| {
+        parts
+            .into_iter()
+            .map(|(part, spaced)| if spaced { part + " " } else { part })
+            .collect()
+    })
+}
+
+/// Source built out of fragments, cut short before the first lexing error
+fn lexable() -> impl Strategy<Value = String> {
+    token_shaped().prop_map(|mut src| {
+        if let (_, Some(err)) = lex_with_error(&src) {
+            src.truncate(err.location.start as usize);
+        }
+        src
+    })
+}
+
+/// An edit somewhere in lexable source, replacing up to 8 bytes with a fragment
+fn edited() -> impl Strategy<Value = (String, TextEdit)> {
+    (
+        lexable(),
+        any::<prop::sample::Index>(),
+        0..8usize,
+        fragment(),
+    )
+        .prop_map(|(src, at, len, text)| {
+            // fragments are ASCII, so every byte is a character boundary
+            let start = at.index(src.len() + 1);
+            let end = (start + len).min(src.len());
+            let edit = TextEdit::new(Span::new(start as u32, end as u32), text);
+            (src, edit)
+        })
+}
+
+/// Checks that spans stay within `src`, on character boundaries, and never overlap or go backwards
+fn assert_spans(src: &str) {
+    let (tokens, error) = lex_with_error(src);
+    let mut last = 0;
+
+    for tok in &tokens {
+        let span = source_span(tok);
+        assert!(
+            span.start <= span.end,
+            "{tok:?} ends before it starts in {src:?}"
+        );
+        assert!(
+            span.end as usize <= src.len(),
+            "{tok:?} is out of bounds in {src:?}"
+        );
+        assert!(
+            src.is_char_boundary(span.start as usize) && src.is_char_boundary(span.end as usize),
+            "{tok:?} splits a character in {src:?}"
+        );
+        assert!(
+            span.start >= last,
+            "{tok:?} overlaps the previous token in {src:?}"
+        );
+        last = span.end;
+    }
+
+    if let Some(err) = error {
+        let span = err.location;
+        assert!(
+            span.start <= span.end,
+            "{err:?} ends before it starts in {src:?}"
+        );
+        assert!(
+            span.end as usize <= src.len(),
+            "{err:?} is out of bounds in {src:?}"
+        );
+        assert!(
+            src.is_char_boundary(span.start as usize) && src.is_char_boundary(span.end as usize),
+            "{err:?} splits a character in {src:?}"
+        );
+    }
+}
+
+/// Tokens whose `Display` is the same as their source
+fn displays_faithfully(tok: &Token) -> bool {
+    !matches!(
+        tok.kind(),
+        TokenKind::Comment | TokenKind::DocComment | TokenKind::Control
+    ) && !matches!(tok, Token::Str { .. })
+}
+
+proptest! {
+    #[test]
+    fn never_panics_on_any_text(src in any::<String>()) {
+        assert_spans(&src);
+    }
+
+    #[test]
+    fn never_panics_on_token_shaped_text(src in token_shaped()) {
+        assert_spans(&src);
+    }
+
+    #[test]
+    fn never_panics_on_text_cut_short(
+        src in token_shaped(),
+        tail in "(//[/!]?|/\\*|\")\\PC{0,4}",
+    ) {
+        assert_spans(&(src + &tail));
+    }
+
+    #[test]
+    fn display_relexes_to_the_same_tokens(src in token_shaped()) {
+        let tokens: Vec<Token> = lex(&src)
+            .into_iter()
+            .map(|(tok, _, _)| tok)
+            .filter(displays_faithfully)
+            .collect();
+
+        let printed: Vec<String> = tokens.iter().map(Token::to_string).collect();
+        let relexed: Vec<Token> = lex(&printed.join(" "))
+            .into_iter()
+            .map(|(tok, _, _)| tok)
+            .collect();
+
+        prop_assert_eq!(relexed, tokens);
+    }
+
+    #[test]
+    fn printed_tokens_relex_to_the_same_tokens(src in token_shaped()) {
+        let tokens: Vec<Token> = lex(&src).into_iter().map(|(tok, _, _)| tok).collect();
+        let relexed: Vec<Token> = lex(&print_tokens(&tokens))
+            .into_iter()
+            .map(|(tok, _, _)| tok)
+            .collect();
+
+        prop_assert_eq!(relexed, tokens);
+    }
+
+    #[test]
+    fn printed_spanned_tokens_relex_to_the_same_tokens(src in token_shaped()) {
+        let spanned = lex(&src);
+        let tokens: Vec<Token> = spanned.iter().map(|(tok, _, _)| tok.clone()).collect();
+        let relexed: Vec<Token> = lex(&print_spanned(&spanned))
+            .into_iter()
+            .map(|(tok, _, _)| tok)
+            .collect();
+
+        prop_assert_eq!(relexed, tokens);
+    }
+
+    #[test]
+    fn zero_copy_lexer_matches_on_any_text(src in any::<String>()) {
+        prop_assert_eq!(lex_borrowed(&src), lex_with_error(&src));
+    }
+
+    #[test]
+    fn zero_copy_lexer_matches_on_token_shaped_text(src in token_shaped()) {
+        prop_assert_eq!(lex_borrowed(&src), lex_with_error(&src));
+    }
+
+    #[test]
+    fn reader_lexer_matches_on_any_text(src in any::<String>(), capacity in 1..8usize) {
+        prop_assert_eq!(lex_reader(&src, capacity), lex_with_error(&src));
+    }
+
+    #[test]
+    fn reader_lexer_matches_on_token_shaped_text(src in token_shaped(), capacity in 1..8usize) {
+        prop_assert_eq!(lex_reader(&src, capacity), lex_with_error(&src));
+    }
+
+    #[test]
+    fn relexing_an_edit_matches_lexing_it_all_again((src, edit) in edited()) {
+        let edited = edit.apply(&src);
+        let (mut tokens, before) = lex_with_error(&src);
+        let (expected, after) = lex_with_error(&edited);
+        prop_assume!(before.is_none() && after.is_none());
+
+        relex(&tokens, &edited, &edit).unwrap().apply(&mut tokens);
+        prop_assert_eq!(tokens, expected);
+    }
+}
@@ -0,0 +1,80 @@
+//! Golden snapshot tests for the lexer
+//! Every `.bs` file under `tests/snapshots/lexer` is lexed and compared against the `.snap` file next to it.
+//! Run with `BAROS_UPDATE_SNAPSHOTS=1` to write the current output as the new snapshots.
+use baros::parser::lexer::lexer_from_str;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Lexes `src` up to the end of the file or the first error, one token per line
+fn render(src: &str) -> String {
+    let mut out = String::new();
+
+    for result in lexer_from_str(src) {
+        match result {
+            Ok((tok, start, end)) => out.push_str(&format!("{start}..{end} {tok:?}\n")),
+            Err(err) => {
+                let span = err.location;
+                out.push_str(&format!(
+                    "{}..{} error {:?}\n",
+                    span.start, span.end, err.error
+                ));
+                // the lexer does not advance past errors
+                break;
+            }
+        }
+    }
+
+    out
+}
+
+/// Collects every fixture in `dir` and its subdirectories
+fn fixtures(dir: &Path, found: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).expect("read snapshot directory") {
+        let path = entry.expect("read snapshot entry").path();
+
+        if path.is_dir() {
+            fixtures(&path, found);
+        } else if path.extension().is_some_and(|ext| ext == "bs") {
+            found.push(path);
+        }
+    }
+}
+
+#[test]
+fn lexer_snapshots() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots/lexer");
+    let update = std::env::var_os("BAROS_UPDATE_SNAPSHOTS").is_some();
+
+    let mut paths = Vec::new();
+    fixtures(&root, &mut paths);
+    paths.sort();
+    assert!(!paths.is_empty(), "no fixtures found in {}", root.display());
+
+    let mut failures = Vec::new();
+
+    for path in paths {
+        let src = fs::read_to_string(&path).expect("read fixture");
+        let actual = render(&src);
+        let snap = path.with_extension("snap");
+
+        if update {
+            fs::write(&snap, &actual).expect("write snapshot");
+            continue;
+        }
+
+        match fs::read_to_string(&snap) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => failures.push(format!(
+                "{} changed\n--- expected\n{expected}+++ actual\n{actual}",
+                snap.display()
+            )),
+            Err(_) => failures.push(format!("{} is missing\n{actual}", snap.display())),
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{}\nrerun with BAROS_UPDATE_SNAPSHOTS=1 to accept the changes",
+        failures.join("\n")
+    );
+}
@@ -0,0 +1,205 @@
+//! Tests for loading manifests and the packages they depend on
+use baros::package::error::PackageErrorType;
+use baros::package::graph::PackageGraph;
+use baros::package::manifest::{discover, Manifest, Target};
+use common::{package, Scratch};
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+#[test]
+fn manifest_defaults() {
+    let manifest = Manifest::parse("[package]\nname = \"app\"\nversion = \"1.2.3\"").unwrap();
+
+    assert_eq!(manifest.package.version, semver::Version::new(1, 2, 3));
+    assert_eq!(manifest.package.entry, Path::new("src/main.bs"));
+    assert_eq!(manifest.package.source_dirs, [Path::new("src")]);
+    assert_eq!(manifest.package.target, Target::Bytecode);
+    assert!(manifest.dependencies.is_empty());
+}
+
+#[test]
+fn manifest_fields() {
+    let manifest = Manifest::parse(
+        r#"
+        [package]
+        name = "app"
+        version = "0.1.0-beta.1"
+        entry = "lib/app.bs"
+        source-dirs = ["lib", "generated"]
+        target = "wasm"
+
+        [dependencies]
+        util = { path = "../util" }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(manifest.package.entry, Path::new("lib/app.bs"));
+    assert_eq!(manifest.package.source_dirs.len(), 2);
+    assert_eq!(manifest.package.target, Target::Wasm);
+    assert_eq!(
+        manifest.dependencies["util"].path(),
+        Some(Path::new("../util"))
+    );
+}
+
+#[test]
+fn invalid_manifests() {
+    let is_syntax =
+        |text: &str| matches!(Manifest::parse(text), Err(PackageErrorType::Syntax { .. }));
+
+    assert!(is_syntax("[package]\nname = \"app\""));
+    assert!(is_syntax("[package]\nname = \"app\"\nversion = \"1.0\""));
+    assert!(is_syntax(
+        "[package]\nname = \"app\"\nversion = \"1.0.0\"\ntarget = \"jvm\""
+    ));
+    assert!(is_syntax(
+        "[package]\nname = \"app\"\nversion = \"1.0.0\"\nauthor = \"me\""
+    ));
+
+    for name in ["My App", "app-cli", "1app", "_app", "mod", ""] {
+        assert_eq!(
+            Manifest::parse(&format!(
+                "[package]\nname = \"{name}\"\nversion = \"1.0.0\""
+            )),
+            Err(PackageErrorType::InvalidName { name: name.into() })
+        );
+    }
+}
+
+#[test]
+fn discovers_manifests_in_parent_directories() {
+    let dir = Scratch::new("packages-discover");
+    let manifest = package(&dir, "app", "0.1.0", "");
+
+    assert_eq!(
+        discover(&dir.join("app-0.1.0/src/nested")).unwrap(),
+        manifest
+    );
+    assert_eq!(
+        discover(&dir).unwrap_err().error,
+        PackageErrorType::ManifestNotFound
+    );
+}
+
+#[test]
+fn dependencies_come_first() {
+    let dir = Scratch::new("packages-order");
+    let app = package(
+        &dir,
+        "app",
+        "0.1.0",
+        "json = { path = \"../json-0.1.0\" }\nutil = { path = \"../util-0.1.0\" }",
+    );
+    let _ = package(
+        &dir,
+        "json",
+        "0.1.0",
+        r#"util = { path = "../util-0.1.0" }"#,
+    );
+    let _ = package(&dir, "util", "0.1.0", "");
+
+    let graph = PackageGraph::load(&app).unwrap();
+    let names: Vec<_> = graph
+        .packages()
+        .map(|(_, package)| package.name())
+        .collect();
+    assert_eq!(names, ["util", "json", "app"]);
+
+    let root = graph.package(graph.root());
+    assert_eq!(root.name(), "app");
+    assert_eq!(root.dependencies.len(), 2);
+
+    let files = root.source_files().unwrap();
+    assert_eq!(files.len(), 2);
+    assert!(files[0].ends_with("src/main.bs"));
+    assert!(files[1].ends_with("src/nested/util.bs"));
+}
+
+#[test]
+fn dependency_cycles() {
+    let dir = Scratch::new("packages-cycle");
+    let a = package(&dir, "a", "0.1.0", r#"b = { path = "../b-0.1.0" }"#);
+    let _ = package(&dir, "b", "0.1.0", r#"c = { path = "../c-0.1.0" }"#);
+    let _ = package(&dir, "c", "0.1.0", r#"a = { path = "../a-0.1.0" }"#);
+
+    assert_eq!(
+        PackageGraph::load(&a).unwrap_err().error,
+        PackageErrorType::DependencyCycle {
+            cycle: vec!["a".into(), "b".into(), "c".into(), "a".into()]
+        }
+    );
+}
+
+#[test]
+fn dependencies_must_match_their_names() {
+    let dir = Scratch::new("packages-mismatch");
+    let app = package(
+        &dir,
+        "app",
+        "0.1.0",
+        r#"util = { path = "../utils-0.1.0" }"#,
+    );
+    let _ = package(&dir, "utils", "0.1.0", "");
+
+    assert_eq!(
+        PackageGraph::load(&app).unwrap_err().error,
+        PackageErrorType::NameMismatch {
+            expected: "util".into(),
+            found: "utils".into()
+        }
+    );
+}
+
+#[test]
+fn layout_is_checked() {
+    let dir = Scratch::new("packages-layout");
+    let app = package(&dir, "app", "0.1.0", "");
+
+    fs::remove_file(dir.join("app-0.1.0/src/main.bs")).unwrap();
+    assert_eq!(
+        PackageGraph::load(&app).unwrap_err().error,
+        PackageErrorType::MissingEntry {
+            entry: "src/main.bs".into()
+        }
+    );
+
+    let text = fs::read_to_string(&app).unwrap();
+    fs::write(
+        &app,
+        text.replace("[dependencies]", "entry = \"main.bs\"\n\n[dependencies]"),
+    )
+    .unwrap();
+    fs::write(dir.join("app-0.1.0/main.bs"), "main").unwrap();
+    assert_eq!(
+        PackageGraph::load(&app).unwrap_err().error,
+        PackageErrorType::EntryOutsideSources {
+            entry: "main.bs".into()
+        }
+    );
+}
+
+#[test]
+fn source_dirs_and_entry_can_start_with_dot() {
+    let dir = Scratch::new("packages-dot");
+    let app = package(&dir, "app", "0.1.0", "");
+    let text = fs::read_to_string(&app).unwrap();
+
+    for layout in [
+        "source-dirs = [\".\"]",
+        "source-dirs = [\"./src\"]",
+        "source-dirs = [\"src\"]\nentry = \"./src/main.bs\"",
+    ] {
+        fs::write(
+            &app,
+            text.replace(
+                "\n\n[dependencies]",
+                &format!("\n{layout}\n\n[dependencies]"),
+            ),
+        )
+        .unwrap();
+        assert!(PackageGraph::load(&app).is_ok(), "{layout}");
+    }
+}
@@ -0,0 +1,129 @@
+//! Tests for lexing from a reader when the source is not valid UTF-8 or can't be read
+use baros::parser::error::{LexError, LexErrorType};
+use baros::parser::lexer::SpannedToken;
+use baros::parser::reader::lexer_from_reader;
+use baros::parser::token::Token;
+use baros::util::Span;
+use std::io::{self, BufReader, ErrorKind, Read};
+
+/// Lexes `bytes` up to the first error, one byte at a time
+fn lex(bytes: &[u8]) -> (Vec<SpannedToken>, Option<LexError>) {
+    let mut tokens = Vec::new();
+
+    for result in lexer_from_reader(BufReader::with_capacity(1, bytes)).take(bytes.len() + 1) {
+        match result {
+            Ok(tok) => tokens.push(tok),
+            Err(err) => return (tokens, Some(err)),
+        }
+    }
+
+    (tokens, None)
+}
+
+fn invalid_utf8(start: u32, end: u32) -> Option<LexError> {
+    Some(LexError::new(
+        LexErrorType::InvalidUtf8,
+        Span::new(start, end),
+    ))
+}
+
+fn name(name: &str, start: u32, end: u32) -> SpannedToken {
+    (Token::Identifier { name: name.into() }, start, end)
+}
+
+#[test]
+fn multibyte_characters_are_decoded() {
+    let (tokens, error) = lex("\"é\" + \"名前\"".as_bytes());
+    let string = |value: &str, start, end| {
+        (
+            Token::Str {
+                value: value.into(),
+            },
+            start,
+            end,
+        )
+    };
+
+    assert_eq!(error, None);
+    assert_eq!(
+        tokens,
+        [
+            string("é", 0, 4),
+            (Token::Plus, 5, 6),
+            string("名前", 7, 15)
+        ]
+    );
+}
+
+#[test]
+fn invalid_lead_byte() {
+    assert_eq!(
+        lex(b"a b \xFF c"),
+        (vec![name("a", 0, 1), name("b", 2, 3)], invalid_utf8(4, 5))
+    );
+}
+
+#[test]
+fn missing_continuation_byte() {
+    assert_eq!(
+        lex(b"a  \xE5\x90 b"),
+        (vec![name("a", 0, 1)], invalid_utf8(3, 5))
+    );
+    assert_eq!(lex(b"a  \xC3"), (vec![name("a", 0, 1)], invalid_utf8(3, 4)));
+}
+
+#[test]
+fn overlong_encodings_and_surrogates() {
+    assert_eq!(lex(b"\xE0\x80\xAF").1, invalid_utf8(0, 3));
+    assert_eq!(lex(b"\xED\xA0\x80").1, invalid_utf8(0, 3));
+}
+
+#[test]
+fn cut_short_tokens_are_replaced() {
+    // without the invalid byte this string would be terminated
+    assert_eq!(lex(b"\"ab\xFF\"").1, invalid_utf8(3, 4));
+    assert_eq!(lex(b"ab\xFF"), (vec![], invalid_utf8(2, 3)));
+}
+
+#[test]
+fn errors_are_sticky() {
+    let mut lexer = lexer_from_reader(BufReader::new(&b"\xFF"[..]));
+
+    for _ in 0..3 {
+        assert_eq!(lexer.next(), Some(Err(invalid_utf8(0, 1).unwrap())));
+    }
+}
+
+/// Reads `data`, then fails
+struct Failing<'a> {
+    data: &'a [u8],
+}
+
+impl Read for Failing<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.data.is_empty() {
+            return Err(io::Error::from(ErrorKind::BrokenPipe));
+        }
+
+        self.data.read(buf)
+    }
+}
+
+#[test]
+fn read_failures() {
+    let reader = BufReader::with_capacity(1, Failing { data: b"a  b" });
+    let results: Vec<_> = lexer_from_reader(reader).take(2).collect();
+
+    assert_eq!(
+        results,
+        [
+            Ok(name("a", 0, 1)),
+            Err(LexError::new(
+                LexErrorType::Io {
+                    kind: ErrorKind::BrokenPipe
+                },
+                Span::new(4, 4)
+            ))
+        ]
+    );
+}
@@ -0,0 +1,379 @@
+//! Tests for publishing to a local registry and resolving dependencies on it
+use baros::package::commands::{self, lockfile_path, VENDOR_DIR};
+use baros::package::error::PackageErrorType;
+use baros::package::graph::PackageGraph;
+use baros::package::lockfile::Lockfile;
+use baros::package::manifest::{Dependency, Manifest, MANIFEST_NAME};
+use baros::package::registry::Registry;
+use baros::package::resolver::resolve;
+use common::{package, Scratch};
+use semver::{Version, VersionReq};
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+/// A fresh directory for a test, holding a registry in `registry/`
+fn scratch(test: &str) -> (Scratch, Registry) {
+    let dir = Scratch::new(&format!("registry-{test}"));
+    let registry = Registry::open(dir.join("registry"));
+    (dir, registry)
+}
+
+/// Publishes a package with the given dependencies
+fn publish(dir: &Path, registry: &Registry, name: &str, version: &str, dependencies: &str) {
+    let manifest = package(dir, name, version, dependencies);
+    let _ = registry
+        .publish(manifest.parent().unwrap())
+        .expect("publish");
+}
+
+fn req(text: &str) -> VersionReq {
+    text.parse().unwrap()
+}
+
+fn versions(lockfile: &Lockfile) -> Vec<String> {
+    lockfile
+        .packages
+        .iter()
+        .map(|locked| format!("{} {}", locked.name, locked.version))
+        .collect()
+}
+
+#[test]
+fn dependency_forms() {
+    let manifest = Manifest::parse(
+        r#"
+        [package]
+        name = "app"
+        version = "0.1.0"
+
+        [dependencies]
+        json = "^1.2"
+        http = { version = "0.3" }
+        util = { path = "../util" }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(manifest.dependencies["json"].version(), Some(&req("^1.2")));
+    assert_eq!(manifest.dependencies["http"].version(), Some(&req("0.3")));
+    assert_eq!(
+        manifest.dependencies["util"],
+        Dependency::Path {
+            path: "../util".into()
+        }
+    );
+
+    let both = "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nutil = { path = \"u\", version = \"1\" }";
+    assert!(matches!(
+        Manifest::parse(both),
+        Err(PackageErrorType::Syntax { .. })
+    ));
+}
+
+#[test]
+fn picks_the_newest_matching_versions() {
+    let (dir, registry) = scratch("newest");
+    publish(&dir, &registry, "util", "1.0.0", "");
+    publish(&dir, &registry, "util", "1.4.2", "");
+    publish(&dir, &registry, "util", "2.0.0", "");
+    publish(&dir, &registry, "util", "1.5.0-beta.1", "");
+    publish(&dir, &registry, "json", "0.3.1", "util = \"^1.1\"");
+
+    let lockfile = resolve(
+        &[("json".into(), req("0.3"))],
+        &registry,
+        &Lockfile::default(),
+    )
+    .unwrap();
+    assert_eq!(versions(&lockfile), ["json 0.3.1", "util 1.4.2"]);
+    assert_eq!(lockfile.packages[0].dependencies, ["util"]);
+}
+
+#[test]
+fn backtracks_out_of_conflicts() {
+    let (dir, registry) = scratch("backtrack");
+    publish(&dir, &registry, "util", "1.0.0", "");
+    publish(&dir, &registry, "util", "2.0.0", "");
+    publish(&dir, &registry, "json", "1.0.0", "util = \"^1\"");
+    publish(&dir, &registry, "json", "2.0.0", "util = \"^2\"");
+    publish(&dir, &registry, "http", "1.0.0", "util = \"^1\"");
+
+    let requirements = [("json".into(), req("*")), ("http".into(), req("1"))];
+    let lockfile = resolve(&requirements, &registry, &Lockfile::default()).unwrap();
+    assert_eq!(
+        versions(&lockfile),
+        ["http 1.0.0", "json 1.0.0", "util 1.0.0"]
+    );
+}
+
+#[test]
+fn reports_unresolvable_requirements() {
+    let (dir, registry) = scratch("conflict");
+    publish(&dir, &registry, "util", "1.0.0", "");
+
+    let err = resolve(
+        &[("util".into(), req("^2"))],
+        &registry,
+        &Lockfile::default(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.error,
+        PackageErrorType::NoMatchingVersion {
+            name: "util".into(),
+            requirements: vec![req("^2")]
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "{}: no published version of `util` matches ^2",
+            registry.root().display()
+        )
+    );
+
+    let err = resolve(
+        &[("nope".into(), req("1"))],
+        &registry,
+        &Lockfile::default(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.error,
+        PackageErrorType::UnknownPackage {
+            name: "nope".into()
+        }
+    );
+}
+
+#[test]
+fn publishing_checks_the_package() {
+    let (dir, registry) = scratch("publish");
+    publish(&dir, &registry, "util", "1.0.0", "");
+
+    let again = package(&dir, "util", "1.0.0", "");
+    assert_eq!(
+        registry.publish(again.parent().unwrap()).unwrap_err().error,
+        PackageErrorType::AlreadyPublished {
+            name: "util".into(),
+            version: Version::new(1, 0, 0)
+        }
+    );
+
+    let local = package(&dir, "app", "1.0.0", "util = { path = \"../util-1.0.0\" }");
+    assert_eq!(
+        registry.publish(local.parent().unwrap()).unwrap_err().error,
+        PackageErrorType::PathDependency {
+            name: "util".into()
+        }
+    );
+
+    let published = registry.package_dir("util", &Version::new(1, 0, 0));
+    assert!(published.join(MANIFEST_NAME).is_file());
+    assert!(published.join("src/main.bs").is_file());
+}
+
+#[test]
+fn add_update_and_lock() {
+    let (dir, registry) = scratch("commands");
+    publish(&dir, &registry, "util", "1.0.0", "");
+    let app = package(&dir, "app", "0.1.0", "");
+
+    let added = commands::add(&app, &registry, "util", None).unwrap();
+    assert_eq!(added, req("^1.0.0"));
+
+    let text = fs::read_to_string(&app).unwrap();
+    assert!(text.starts_with("# app\n"), "comments are kept");
+    assert!(text.ends_with("[dependencies]\nutil = \"^1.0.0\"\n"));
+
+    let lockfile = Lockfile::load(&lockfile_path(&app)).unwrap();
+    assert_eq!(versions(&lockfile), ["util 1.0.0"]);
+
+    // locked versions stay put until they are updated
+    publish(&dir, &registry, "util", "1.1.0", "");
+    assert_eq!(
+        versions(&commands::lock(&app, &registry).unwrap()),
+        ["util 1.0.0"]
+    );
+    assert_eq!(
+        versions(&commands::update(&app, &registry, &["util"]).unwrap()),
+        ["util 1.1.0"]
+    );
+
+    let graph = PackageGraph::load_locked(
+        &app,
+        &registry,
+        &Lockfile::load(&lockfile_path(&app)).unwrap(),
+    )
+    .unwrap();
+    let names: Vec<_> = graph
+        .packages()
+        .map(|(_, package)| package.name())
+        .collect();
+    assert_eq!(names, ["util", "app"]);
+}
+
+#[test]
+fn failed_adds_leave_the_manifest_alone() {
+    let (dir, registry) = scratch("failed-add");
+    publish(&dir, &registry, "util", "1.0.0", "");
+    let app = package(&dir, "app", "0.1.0", "");
+    let before = fs::read_to_string(&app).unwrap();
+
+    assert!(commands::add(&app, &registry, "util", Some(req("^3"))).is_err());
+    assert!(commands::add(&app, &registry, "missing", None).is_err());
+    assert_eq!(fs::read_to_string(&app).unwrap(), before);
+}
+
+#[test]
+fn vendored_packages_stand_in_for_the_registry() {
+    let (dir, registry) = scratch("vendor");
+    publish(&dir, &registry, "util", "1.0.0", "");
+    publish(&dir, &registry, "util", "2.0.0", "");
+    publish(&dir, &registry, "json", "1.0.0", "util = \"1\"");
+    let app = package(&dir, "app", "0.1.0", "json = \"1\"");
+
+    let vendored = commands::vendor(&app, &registry).unwrap();
+    assert_eq!(vendored.root(), app.parent().unwrap().join(VENDOR_DIR));
+    assert_eq!(vendored.versions("util").unwrap().len(), 1);
+
+    let lockfile = Lockfile::load(&lockfile_path(&app)).unwrap();
+    assert_eq!(
+        resolve(&[("json".into(), req("1"))], &vendored, &lockfile).unwrap(),
+        lockfile
+    );
+    assert!(PackageGraph::load_locked(&app, &vendored, &lockfile).is_ok());
+}
+
+#[test]
+fn publishing_rejects_absolute_source_dirs() {
+    let (dir, registry) = scratch("absolute");
+    let manifest = package(&dir, "util", "1.0.0", "");
+    let root = manifest.parent().unwrap();
+    let src = root.join("src");
+    let text = fs::read_to_string(&manifest).unwrap().replace(
+        "\n\n[dependencies]",
+        &format!(
+            "\nentry = \"{}\"\nsource-dirs = [\"{}\"]\n\n[dependencies]",
+            src.join("main.bs").display(),
+            src.display()
+        ),
+    );
+    fs::write(&manifest, text).unwrap();
+
+    assert_eq!(
+        registry.publish(root).unwrap_err().error,
+        PackageErrorType::PathOutsidePackage { path: src.clone() }
+    );
+    assert_eq!(fs::read_to_string(src.join("main.bs")).unwrap(), "util");
+    assert!(registry.versions("util").is_err());
+}
+
+#[test]
+fn publishing_rejects_source_dirs_outside_the_package() {
+    let (dir, registry) = scratch("parent-dir");
+    let manifest = package(&dir, "util", "1.0.0", "");
+    let root = manifest.parent().unwrap();
+    let text = fs::read_to_string(&manifest).unwrap().replace(
+        "\n\n[dependencies]",
+        "\nsource-dirs = [\"src\", \"../shared\"]\n\n[dependencies]",
+    );
+    fs::write(&manifest, text).unwrap();
+    fs::create_dir_all(dir.join("shared")).unwrap();
+
+    assert_eq!(
+        registry.publish(root).unwrap_err().error,
+        PackageErrorType::PathOutsidePackage {
+            path: "../shared".into()
+        }
+    );
+    assert!(!registry.root().join("packages/util/shared").exists());
+}
+
+#[test]
+fn vendoring_never_deletes_its_own_registry() {
+    let (dir, registry) = scratch("vendor-self");
+    publish(&dir, &registry, "util", "1.0.0", "");
+    let app = package(&dir, "app", "0.1.0", "util = \"1\"");
+    let vendored = commands::vendor(&app, &registry).unwrap();
+
+    let err = commands::vendor(&app, &vendored).unwrap_err();
+    assert_eq!(
+        err.error,
+        PackageErrorType::RegistryInVendorDir {
+            vendor: vendored.root().to_path_buf()
+        }
+    );
+    assert_eq!(vendored.versions("util").unwrap().len(), 1);
+
+    let nested = Registry::open(vendored.root().join("nested"));
+    fs::create_dir_all(nested.root()).unwrap();
+    assert!(matches!(
+        commands::vendor(&app, &nested).unwrap_err().error,
+        PackageErrorType::RegistryInVendorDir { .. }
+    ));
+}
+
+#[test]
+fn failed_vendoring_keeps_the_old_vendor_directory() {
+    let (dir, registry) = scratch("vendor-failed");
+    publish(&dir, &registry, "util", "1.0.0", "");
+    let app = package(&dir, "app", "0.1.0", "util = \"1\"");
+    let vendored = commands::vendor(&app, &registry).unwrap();
+
+    fs::remove_dir_all(registry.package_dir("util", &Version::new(1, 0, 0))).unwrap();
+    assert!(commands::vendor(&app, &registry).is_err());
+    assert_eq!(vendored.versions("util").unwrap().len(), 1);
+    assert!(vendored
+        .package_dir("util", &Version::new(1, 0, 0))
+        .join(MANIFEST_NAME)
+        .is_file());
+
+    let staging = app.parent().unwrap().join(format!(".{VENDOR_DIR}.tmp"));
+    assert!(!staging.exists());
+}
+
+#[test]
+fn publishing_never_copies_the_registry_into_itself() {
+    let (dir, _) = scratch("publish-self");
+    let manifest = package(&dir, "util", "1.0.0", "");
+    let root = manifest.parent().unwrap();
+
+    for registry in [root.join("src/registry"), root.join("src/nested")] {
+        let registry = Registry::open(registry);
+        assert_eq!(
+            registry.publish(root).unwrap_err().error,
+            PackageErrorType::RegistryInSourceDir { dir: "src".into() }
+        );
+        assert!(!registry.root().join("packages").exists());
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn failed_publishing_leaves_nothing_behind() {
+    let (dir, registry) = scratch("publish-failed");
+    let manifest = package(&dir, "util", "1.0.0", "");
+    let root = manifest.parent().unwrap();
+    std::os::unix::fs::symlink(root.join("missing.bs"), root.join("src/broken.bs")).unwrap();
+
+    assert!(registry.publish(root).is_err());
+    assert!(!registry
+        .root()
+        .join("packages/util")
+        .read_dir()
+        .unwrap()
+        .any(|_| true));
+    assert!(registry.versions("util").is_err());
+
+    fs::remove_file(root.join("src/broken.bs")).unwrap();
+    let _ = registry.publish(root).unwrap();
+    let published = registry.package_dir("util", &Version::new(1, 0, 0));
+    assert_eq!(
+        fs::read_to_string(published.join("src/main.bs")).unwrap(),
+        "util"
+    );
+    assert!(!published.join("src/broken.bs").exists());
+}
@@ -0,0 +1,86 @@
+//! Tests for looking up file-qualified spans in a `SourceDatabase`
+use baros::parser::error::LexErrorType;
+use baros::source::{token_span, SourceDatabase};
+use baros::util::{FileId, Span};
+use std::path::Path;
+
+#[test]
+fn files_get_their_own_ids() {
+    let mut db = SourceDatabase::new();
+    let main = db.add("src/main.bs", "main");
+    let lib = db.add("src/lib.bs", "lib");
+
+    assert_ne!(main, lib);
+    assert_eq!(db.len(), 2);
+    assert_eq!(db.file_id("src/lib.bs"), Some(lib));
+    assert_eq!(db.file(main).unwrap().path(), Path::new("src/main.bs"));
+    assert_eq!(db.snippet(Span::in_file(lib, 0, 3)), Some("lib"));
+}
+
+#[test]
+fn adding_a_path_again_replaces_its_text() {
+    let mut db = SourceDatabase::new();
+    let first = db.add("a.bs", "old");
+    let second = db.add("a.bs", "new text");
+
+    assert_eq!(first, second);
+    assert_eq!(db.len(), 1);
+    assert_eq!(db.file(first).unwrap().text(), "new text");
+}
+
+#[test]
+fn spans_without_a_file_are_not_found() {
+    let mut db = SourceDatabase::new();
+    let _ = db.add("a.bs", "abc");
+
+    assert_eq!(Span::default().file, FileId::UNKNOWN);
+    assert_eq!(db.snippet(Span::new(0, 1)), None);
+    assert_eq!(db.location(Span::new(0, 1)), None);
+}
+
+#[test]
+fn locations_count_lines_like_the_lexer() {
+    let mut db = SourceDatabase::new();
+    let file = db.add("a.bs", "a\r\nb\rcé d\ne");
+
+    let at = |pos| {
+        db.location(Span::in_file(file, pos, pos))
+            .unwrap()
+            .to_string()
+    };
+
+    assert_eq!(at(0), "a.bs:1:1");
+    assert_eq!(at(3), "a.bs:2:1");
+    assert_eq!(at(5), "a.bs:3:1");
+    assert_eq!(at(9), "a.bs:3:4");
+    assert_eq!(at(11), "a.bs:4:1");
+
+    // é takes two bytes but one column
+    assert_eq!(at(8), "a.bs:3:3");
+}
+
+#[test]
+fn lexing_qualifies_spans() {
+    let mut db = SourceDatabase::new();
+    let good = db.add("good.bs", "a // note");
+    let bad = db.add("bad.bs", "a;");
+
+    let tokens = db.lex(good).unwrap();
+    assert_eq!(db.snippet(token_span(good, &tokens[1])), Some("// note"));
+
+    let err = db.lex(bad).unwrap_err();
+    assert_eq!(err.error, LexErrorType::DisallowedToken { tok: ';' });
+    assert_eq!(err.location, Span::in_file(bad, 1, 1));
+    assert_eq!(db.location(err.location).unwrap().to_string(), "bad.bs:1:2");
+}
+
+#[test]
+fn tokens_at_the_end_of_the_file_end_with_it() {
+    let mut db = SourceDatabase::new();
+    let file = db.add("a.bs", "a // é");
+
+    let tokens = db.lex(file).unwrap();
+    let span = token_span(file, &tokens[1]);
+    assert_eq!(db.snippet(span), Some("// é"));
+    assert_eq!(db.location(span).unwrap().to_string(), "a.bs:1:3");
+}
@@ -0,0 +1,133 @@
+//! Tests for lookahead and rewinding in `TokenStream`
+use baros::parser::borrowed::{borrowed_lexer_from_str, BorrowedToken};
+use baros::parser::error::LexErrorType;
+use baros::parser::reader::lexer_from_reader;
+use baros::parser::stream::{token_stream_from_str, TokenStream};
+use baros::parser::token::Token;
+
+/// The token kinds a stream yields up to and including the end of the file
+fn drain(src: &str) -> Vec<Token> {
+    let mut stream = token_stream_from_str(src);
+    let mut tokens = Vec::new();
+
+    loop {
+        let (tok, _, _) = stream.bump().expect("lexes");
+        tokens.push(tok.clone());
+
+        if tok == Token::Eof {
+            return tokens;
+        }
+    }
+}
+
+#[test]
+fn peeking_does_not_consume() {
+    let mut stream = token_stream_from_str("a + b");
+
+    assert_eq!(stream.peek_nth(2).unwrap().1, 4);
+    assert_eq!(stream.peek_nth(1).unwrap().0, Token::Plus);
+    assert_eq!(stream.peek().unwrap().1, 0);
+    assert_eq!(stream.bump().unwrap().1, 0);
+    assert_eq!(stream.bump().unwrap().0, Token::Plus);
+    assert_eq!(stream.peek().unwrap().1, 4);
+}
+
+#[test]
+fn eof_is_sticky() {
+    let mut stream = token_stream_from_str("a");
+
+    assert_eq!(stream.peek_nth(5).unwrap().0, Token::Eof);
+    let _ = stream.bump();
+
+    for _ in 0..3 {
+        assert_eq!(stream.bump().unwrap().0, Token::Eof);
+    }
+}
+
+#[test]
+fn errors_are_sticky() {
+    let mut stream = token_stream_from_str("a ;");
+
+    assert_eq!(
+        stream.peek_nth(3).unwrap_err().error,
+        LexErrorType::DisallowedToken { tok: ';' }
+    );
+    let _ = stream.bump();
+    assert!(stream.bump().is_err());
+    assert!(stream.bump().is_err());
+}
+
+#[test]
+fn eat_only_consumes_a_match() {
+    let mut stream = token_stream_from_str("( )");
+
+    assert!(!stream.eat(&Token::RightParen));
+    assert!(stream.eat(&Token::LeftParen));
+    assert!(stream.eat(&Token::RightParen));
+    assert!(stream.eat(&Token::Eof));
+}
+
+#[test]
+fn rewinding_replays_tokens() {
+    let src = "a + b * c";
+    let mut stream = token_stream_from_str(src);
+    let _ = stream.bump();
+
+    let outer = stream.checkpoint();
+    let _ = stream.bump();
+    let inner = stream.checkpoint();
+    let _ = stream.bump();
+    let _ = stream.bump();
+    stream.rewind(inner);
+    assert_eq!(stream.peek().unwrap().1, 4);
+
+    let _ = stream.bump();
+    stream.rewind(outer);
+    assert_eq!(stream.peek().unwrap().0, Token::Plus);
+
+    let mut rest = Vec::new();
+    loop {
+        let (tok, _, _) = stream.bump().unwrap();
+        rest.push(tok.clone());
+        if tok == Token::Eof {
+            break;
+        }
+    }
+    assert_eq!(rest, drain(src)[1..]);
+}
+
+#[test]
+fn committing_keeps_the_position() {
+    let mut stream = token_stream_from_str("a + b");
+
+    let checkpoint = stream.checkpoint();
+    let _ = stream.bump();
+    let _ = stream.bump();
+    stream.commit(checkpoint);
+
+    assert_eq!(stream.bump().unwrap().1, 4);
+    assert_eq!(stream.bump().unwrap().0, Token::Eof);
+}
+
+#[test]
+fn streams_take_any_token_source() {
+    let src = "let x = \"é\" // end";
+
+    let mut stream = TokenStream::new(lexer_from_reader(src.as_bytes()));
+    let mut tokens = Vec::new();
+    while !stream.eat(&Token::Eof) {
+        tokens.push(stream.bump().unwrap().0);
+    }
+    tokens.push(Token::Eof);
+    assert_eq!(tokens, drain(src));
+
+    let mut stream = TokenStream::new(borrowed_lexer_from_str(src));
+    assert!(stream.eat(&BorrowedToken::Fixed(Token::Let)));
+    assert_eq!(stream.peek_nth(2).unwrap().1, 8);
+    let _ = stream.bump();
+    let _ = stream.bump();
+    assert_eq!(stream.bump().unwrap().0, BorrowedToken::Str("é"));
+    let _ = stream.bump();
+    assert!(stream.eat(&BorrowedToken::Fixed(Token::Eof)));
+    assert!(stream.eat(&BorrowedToken::Fixed(Token::Eof)));
+}
@@ -0,0 +1,113 @@
+//! Differential tests of the generated tree-sitter grammar against the lexer
+//! The test that runs the grammar needs the `tree-sitter` CLI on the path, so it is ignored by default.
+//! CI installs the CLI and runs it with `cargo test --test tree_sitter -- --ignored`.
+use baros::highlight::tree_sitter::rule_name;
+use baros::parser::lexer::{lexer_from_str, source_span};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Fixtures the grammar can't lex like the lexer, which the README explains
+const DIVERGENT: [&str; 1] = ["names.bs"];
+
+/// Runs the tree-sitter CLI in `dir`, returning its output
+fn tree_sitter(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("tree-sitter")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("the tree-sitter CLI is installed");
+
+    assert!(
+        output.status.success(),
+        "tree-sitter {} failed:\n{}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// The rule and byte range of each token the lexer finds in `src`
+fn lexed(src: &str) -> Vec<(String, u32, u32)> {
+    lexer_from_str(src)
+        .map(|result| result.expect("fixtures lex without errors"))
+        .map(|tok| {
+            let span = source_span(&tok);
+            (rule_name(tok.0.kind()).to_string(), span.start, span.end)
+        })
+        .collect()
+}
+
+/// The rule and byte range of each token in the syntax tree `tree-sitter parse` printed for `src`
+fn parsed(src: &str, tree: &str) -> Vec<(String, u32, u32)> {
+    let mut line_starts = vec![0];
+    line_starts.extend(src.match_indices('\n').map(|(i, _)| i as u32 + 1));
+    let offset = |point: &str| {
+        let (row, column) = point.split_once(", ").expect("row and column");
+        line_starts[row.parse::<usize>().unwrap()] + column.parse::<u32>().unwrap()
+    };
+
+    // tokens are the children of source_file, which are indented by one level
+    tree.lines()
+        .filter_map(|line| line.strip_prefix("  ("))
+        .filter(|line| !line.starts_with(' '))
+        .map(|line| {
+            let (rule, range) = line.split_once(" [").expect("node with a range");
+            let (start, end) = range.split_once("] - [").expect("start and end");
+            let end = end.split(']').next().unwrap();
+            (rule.to_string(), offset(start), offset(end))
+        })
+        .collect()
+}
+
+#[test]
+fn parsed_reads_syntax_trees() {
+    let src = "let x = -1 // one\n\"é\"\n";
+    let tree = "\
+(source_file [0, 0] - [2, 0]
+  (keyword [0, 0] - [0, 3])
+  (identifier [0, 4] - [0, 5])
+  (operator [0, 6] - [0, 7])
+  (literal [0, 8] - [0, 10]
+    (int [0, 8] - [0, 10]))
+  (comment [0, 11] - [0, 17])
+  (newline [0, 17] - [1, 0])
+  (literal [1, 0] - [1, 4]
+    (string [1, 0] - [1, 4]))
+  (newline [1, 4] - [2, 0]))
+";
+
+    assert_eq!(parsed(src, tree), lexed(src));
+}
+
+#[test]
+#[ignore = "needs the tree-sitter CLI"]
+fn grammar_lexes_like_the_lexer() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let dir = std::env::temp_dir().join(format!("baros-tree-sitter-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("create grammar directory");
+    fs::copy(
+        root.join("tree-sitter-baros/grammar.js"),
+        dir.join("grammar.js"),
+    )
+    .expect("copy grammar");
+
+    let _ = tree_sitter(&dir, &["generate"]);
+
+    let fixtures: Vec<PathBuf> = fs::read_dir(root.join("tests/snapshots/lexer"))
+        .expect("read fixtures")
+        .map(|entry| entry.expect("read fixture").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "bs"))
+        .filter(|path| !DIVERGENT.iter().any(|name| path.ends_with(name)))
+        .collect();
+
+    for path in fixtures {
+        let src = fs::read_to_string(&path).expect("read fixture");
+        let tree = tree_sitter(&dir, &["parse", path.to_str().expect("utf-8 path")]);
+
+        assert_eq!(parsed(&src, &tree), lexed(&src), "{}", path.display());
+    }
+
+    let _ = fs::remove_dir_all(&dir);
+}
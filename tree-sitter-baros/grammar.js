@@ -0,0 +1,42 @@
+// Generated by baros::highlight::tree_sitter::grammar, do not edit by hand
+module.exports = grammar({
+  name: 'baros',
+
+  extras: $ => [/[ \t\f]/],
+
+  word: $ => $.identifier,
+
+  rules: {
+    source_file: $ => repeat($._token),
+
+    _token: $ => choice($.keyword, $.operator, $.reserved_operator, $.literal, $.comment, $.doc_comment, $.identifier, $.unused_identifier, $.punctuation, $.delimiter, $.newline),
+
+    keyword: $ => choice('alias', 'as', 'async', 'await', 'const', 'default', 'do', 'dyn', 'enum', 'except', 'fun', 'impl', 'import', 'let', 'mod', 'mut', 'pub', 'return', 'self', 'static', 'struct', 'super', 'test', 'trait', 'try', 'type', 'union', 'use', 'var', 'when', 'where', 'if', 'elif', 'else', 'match', 'loop', 'for', 'while', 'continue', 'break', 'in'),
+
+    identifier: $ => /[@A-Za-z][_0-9A-Za-z]*/,
+
+    unused_identifier: $ => /_[_0-9A-Za-z]*/,
+
+    literal: $ => choice($.string, $.float, $.int),
+
+    string: $ => token(seq('"', repeat(choice(/[^"\\]/, /\\([fnrt"\\]|u\{[0-9a-fA-F]{1,6}\})/)), '"')),
+
+    float: $ => /-?[0-9][0-9_]*\.[0-9_]*([eE][+-]?[0-9_]*)?/,
+
+    int: $ => /-?(0[xX][0-9a-fA-F_]+|0[oO][0-7_]+|0[bB][01_]+|[0-9][0-9_]*)/,
+
+    comment: $ => choice(token(seq('//', /[^/!\r\n][^\r\n]*/)), token('//'), token(seq('/*', /([^*]|\*+[^*/])*/, /\*+\//))),
+
+    doc_comment: $ => token(seq('//', /[/!][^\r\n]*/)),
+
+    newline: $ => /\r?\n|\r/,
+
+    operator: $ => choice('%%=', '..=', '<<=', '=..', '=.=', '===', '>>=', '!=', '%%', '%=', '&=', '**', '*=', '+=', '-=', '..', '/=', '<<', '<=', '<>', '==', '>=', '>>', '^=', '|=', '%', '&', '*', '+', '-', '/', '<', '=', '>', '^', '|'),
+
+    reserved_operator: $ => choice('-->', '<--', '<->', '-<', ':>', '<:', '<|', '<~', '>-', '|>', '~>'),
+
+    punctuation: $ => choice('->', '::', '<-', '!', '#', '$', ',', '.', ':', '?', '~'),
+
+    delimiter: $ => choice('(', ')', '[', ']', '{', '}'),
+  },
+});
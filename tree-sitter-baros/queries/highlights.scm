@@ -0,0 +1,13 @@
+; Generated by baros::highlight::tree_sitter::highlights, do not edit by hand
+(keyword) @keyword
+(operator) @operator
+(reserved_operator) @operator.reserved
+(string) @string
+(int) @number
+(float) @number.float
+(comment) @comment
+(doc_comment) @comment.documentation
+(identifier) @variable
+(unused_identifier) @variable.unused
+(punctuation) @punctuation.delimiter
+(delimiter) @punctuation.bracket